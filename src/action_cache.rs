@@ -0,0 +1,160 @@
+use ignore::WalkBuilder;
+use ignore::overrides::OverrideBuilder;
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use uuid::Uuid;
+
+use crate::{ACTION_CACHE_DIR, CACHE_DIR};
+use crate::actions::DescribedAction;
+use crate::entities::environment::BuildEnvironment;
+use crate::rw::{copy_all, remove_path, IgnoreRules};
+use crate::utils::build_relative_path;
+
+/// Вычисляет ключ кэша Действия.
+/// 
+/// Ключ - хэш описания Действия, его команд и значений плейсхолдеров, а также путей и содержимого входных файлов.
+/// Изменение вывода команды или значения секрета, подставляемых в команды, меняет ключ.
+pub(crate) fn cache_key(env: BuildEnvironment, action: &DescribedAction, inputs: &[String]) -> anyhow::Result<String> {
+  let mut hasher = Sha256::new();
+  hash_str(&mut hasher, &serde_json::to_string(&action.action)?);
+  for command in action.commands() {
+    for part in command.cache_key_parts(env)? { hash_str(&mut hasher, &part); }
+  }
+  for output in action.outputs.iter().flatten() { hash_str(&mut hasher, output); }
+  
+  let mut overrides = OverrideBuilder::new(env.build_dir);
+  for input in inputs { overrides.add(input)?; }
+  let walker = WalkBuilder::new(env.build_dir).standard_filters(false).overrides(overrides.build()?).build();
+  
+  let mut files = vec![];
+  for entry in walker {
+    let path = entry?.into_path();
+    if path.is_file() { files.push(path); }
+  }
+  files.sort();
+  
+  for file in files {
+    hash_str(&mut hasher, &file.strip_prefix(env.build_dir)?.to_string_lossy());
+    let mut f = std::fs::File::open(&file)?;
+    hasher.update(f.metadata()?.len().to_le_bytes());
+    std::io::copy(&mut f, &mut hasher)?;
+  }
+  
+  Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Восстанавливает выходные файлы Действия из кэша; возвращает `false`, если записи с таким ключом в кэше нет.
+pub(crate) fn restore(env: BuildEnvironment, action: &DescribedAction, key: &str) -> anyhow::Result<bool> {
+  let entry = entry_path(env.cache_dir, key);
+  if !entry.is_dir() { return Ok(false) }
+  
+  for output in action.outputs.iter().flatten() {
+    let target = env.build_dir.join(build_relative_path(output)?);
+    remove_path(&target)?;
+    copy_all(entry.join(output), target, &mut IgnoreRules::new(&[], &[]))?;
+  }
+  // По времени изменения записи `gc --max-age` определяет, когда она использовалась в последний раз
+  std::fs::File::open(&entry)?.set_modified(SystemTime::now())?;
+  
+  Ok(true)
+}
+
+/// Сохраняет выходные файлы Действия в кэш; возвращает `false`, если Действие создало не все выходные файлы.
+pub(crate) fn store(env: BuildEnvironment, action: &DescribedAction, key: &str) -> anyhow::Result<bool> {
+  let outputs = action.outputs.as_deref().unwrap_or_default();
+  for output in outputs {
+    if !env.build_dir.join(build_relative_path(output)?).exists() { return Ok(false) }
+  }
+  
+  let entry = entry_path(env.cache_dir, key);
+  if entry.is_dir() { return Ok(true) }
+  
+  // Запись собирается во временной папке, чтобы параллельно выполняемые Пайплайны не увидели её частично
+  let tmp = entry.with_extension(format!("tmp-{}", Uuid::new_v4().simple()));
+  std::fs::create_dir_all(&tmp)?;
+  for output in outputs {
+    copy_all(env.build_dir.join(output), tmp.join(output), &mut IgnoreRules::new(&[], &[]))?;
+  }
+  // Если запись уже сохранена другим Пайплайном, временная папка не нужна
+  if std::fs::rename(&tmp, &entry).is_err() { remove_path(&tmp)?; }
+  
+  Ok(true)
+}
+
+/// Запись кэша - папка с копиями выходных файлов Действия.
+/// 
+/// Записи намеренно общие для всех папок сборок и проектов: название проекта в ключ не входит, поэтому одинаковые
+/// Действия над одинаковыми входными файлами (например, в форках одного проекта) используют одну запись.
+fn entry_path(cache_dir: &Path, key: &str) -> PathBuf {
+  cache_dir.join(CACHE_DIR).join(ACTION_CACHE_DIR).join(key)
+}
+
+fn hash_str(hasher: &mut Sha256, s: &str) {
+  hasher.update((s.len() as u64).to_le_bytes());
+  hasher.update(s.as_bytes());
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::actions::Action;
+  use crate::entities::variables::{VarValue, Variable};
+  use crate::tests::TestEnv;
+  
+  /// Кэшируемое Действие с указанной командой.
+  fn action(bash_c: &str) -> DescribedAction {
+    serde_json::from_value(serde_json::json!({
+      "title": "build",
+      "desc": "",
+      "info": "build@0.1.0",
+      "tags": [],
+      "action": { "Custom": { "bash_c": bash_c, "ignore_fails": false, "show_success_output": false, "show_bash_c": false } },
+      "inputs": ["src/**"],
+      "outputs": ["target"],
+    })).unwrap()
+  }
+  
+  #[test]
+  fn keys() {
+    let env = TestEnv::new();
+    env.dir.write("src/main.rs", "fn main() {}");
+    let inputs = vec!["src/**".to_owned()];
+    let key = |bash_c: &str| cache_key(env.env(), &action(bash_c), &inputs).unwrap();
+    
+    let first = key("cargo build");
+    assert_eq!(first.len(), 64);
+    assert_eq!(first, key("cargo build"));
+    assert_ne!(first, key("cargo build --release"));
+    // Файлы вне входных на ключ не влияют
+    env.dir.write("README.md", "readme");
+    assert_eq!(first, key("cargo build"));
+    
+    env.dir.write("src/main.rs", "fn main() { println!(); }");
+    let changed = key("cargo build");
+    assert_ne!(first, changed);
+    std::fs::rename(env.dir.path().join("src/main.rs"), env.dir.path().join("src/lib.rs")).unwrap();
+    assert_ne!(changed, key("cargo build"));
+  }
+  
+  #[test]
+  fn command_output_changes_key() {
+    let mut action = action("docker build -t app:<tag> .");
+    if let Action::Custom(command) = &mut action.action {
+      command.placeholders = Some(vec!["<tag>".to_owned()]);
+      command.replacements = Some(vec![vec![(
+        "<tag>".to_owned(),
+        Variable { title: "tag".to_owned(), is_secret: false, value: VarValue::FromCmd("cat HEAD".to_owned()) },
+      )]]);
+    }
+    // Каждый запуск - новое окружение: вывод команд кэшируется лишь в пределах одного запуска Пайплайна
+    let key = |head: &str| {
+      let env = TestEnv::new();
+      env.dir.write("HEAD", head);
+      cache_key(env.env(), &action, &[]).unwrap()
+    };
+    
+    assert_eq!(key("1f2e3d4"), key("1f2e3d4"));
+    assert_ne!(key("1f2e3d4"), key("5a6b7c8"));
+  }
+}
@@ -18,7 +18,7 @@ use crate::actions::{
   observe::ObserveAction,
 };
 use crate::cmd::{NewActionArgs, CatActionArgs};
-use crate::configs::DeployerGlobalConfig;
+use crate::configs::{DeployerGlobalConfig, WithPipelineAnswers};
 use crate::entities::{
   custom_command::{CustomCommand, specify_bash_c},
   info::{ActionInfo, info2str, str2info, info2str_simple},
@@ -42,6 +42,23 @@ pub(crate) struct DescribedAction {
   /// Список меток для фильтрации действий при выборе из реестра
   pub(crate) tags: Vec<String>,
   pub(crate) action: Action,
+  /// Заголовки Действий Пайплайна, после успешного выполнения которых выполняется Действие.
+  /// 
+  /// Если не указано, Действие выполняется после предыдущего Действия Пайплайна.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub(crate) needs: Option<Vec<String>>,
+  /// Условие выполнения Действия (см. `Condition`).
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub(crate) when: Option<String>,
+  /// Входные файлы Действия - шаблоны путей в формате `.gitignore` относительно папки сборки.
+  /// 
+  /// Если указаны, Действие кэшируется: при неизменных входных файлах и командах выходные файлы
+  /// восстанавливаются из кэша вместо выполнения Действия.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub(crate) inputs: Option<Vec<String>>,
+  /// Выходные файлы и папки Действия (пути относительно папки сборки), сохраняемые в кэш.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub(crate) outputs: Option<Vec<String>>,
 }
 
 #[derive(Deserialize, Serialize, PartialEq, Clone, Debug)]
@@ -164,6 +181,8 @@ impl DescribedAction {
             show_success_output: true,
             show_bash_c: false,
             only_when_fresh: None,
+            timeout: None,
+            retry: None,
           },
         })
       },
@@ -244,6 +263,10 @@ impl DescribedAction {
       info,
       tags,
       action,
+      needs: None,
+      when: None,
+      inputs: None,
+      outputs: None,
     };
     
     if
@@ -258,27 +281,79 @@ impl DescribedAction {
     Ok(described_action)
   }
   
+  /// Спрашивает, использовать ли несовместимое с проектом Действие.
+  /// 
+  /// При неинтерактивной настройке ответ берётся из файла ответов.
+  fn use_incompatible(
+    &self,
+    message: &str,
+    answers: Option<&WithPipelineAnswers>,
+  ) -> anyhow::Result<bool> {
+    match answers {
+      Some(answers) => Ok(!answers.skip_incompatible),
+      None => Ok(inquire::Confirm::new(message).prompt()?),
+    }
+  }
+  
+  /// Возвращает все команды Действия.
+  pub(crate) fn commands(&self) -> Vec<&CustomCommand> {
+    match &self.action {
+      Action::Custom(cmd) => vec![cmd],
+      Action::Check(c_action) => vec![&c_action.command],
+      Action::Observe(o_action) => vec![&o_action.command],
+      Action::ProjectClean(pc_action) => pc_action.additional_commands.iter().collect(),
+      Action::PreBuild(a) | Action::Build(a) | Action::PostBuild(a) | Action::Test(a) => a.commands.iter().collect(),
+      Action::Pack(a) | Action::Deliver(a) | Action::Install(a) => a.commands.iter().collect(),
+      Action::ConfigureDeploy(a) | Action::Deploy(a) | Action::PostDeploy(a) => a.commands.iter().collect(),
+      Action::Interrupt | Action::ForceArtifactsEnplace => vec![],
+    }
+  }
+  
+  /// Возвращает тип Действия.
+  pub(crate) fn kind(&self) -> &'static str {
+    match &self.action {
+      Action::Interrupt => "Interrupt",
+      Action::Custom(_) => "Custom",
+      Action::Check(_) => "Check",
+      Action::ForceArtifactsEnplace => "ForceArtifactsEnplace",
+      Action::PreBuild(_) => "PreBuild",
+      Action::Build(_) => "Build",
+      Action::PostBuild(_) => "PostBuild",
+      Action::Test(_) => "Test",
+      Action::ProjectClean(_) => "ProjectClean",
+      Action::Pack(_) => "Pack",
+      Action::Deliver(_) => "Deliver",
+      Action::Install(_) => "Install",
+      Action::ConfigureDeploy(_) => "ConfigureDeploy",
+      Action::Deploy(_) => "Deploy",
+      Action::PostDeploy(_) => "PostDeploy",
+      Action::Observe(_) => "Observe",
+    }
+  }
+  
   fn setup_buildlike_action(
     &self,
     action: &BuildAction,
     langs: &Vec<ProgrammingLanguage>,
     variables: &[Variable],
     artifacts: &[String],
+    answers: Option<&WithPipelineAnswers>,
   ) -> anyhow::Result<BuildAction> {
     let mut action = action.clone();
     if 
       !langs.iter().any(|l| action.supported_langs.contains(l)) && 
-      !inquire::Confirm::new(
+      !self.use_incompatible(
         &i18n::ACTION_COMPAT_PLS
           .replace("{1}", &info2str_simple(&self.info))
           .replace("{2}", &format!("{:?}", action.supported_langs))
-          .replace("{3}", &format!("{:?}", langs))
-      ).prompt()?
+          .replace("{3}", &format!("{:?}", langs)),
+        answers,
+      )?
     {
       return Ok(BuildAction::default())
     }
     
-    for cmd in &mut action.commands { *cmd = cmd.prompt_setup_for_project(&self.info, variables, artifacts)?; }
+    for cmd in &mut action.commands { *cmd = cmd.prompt_setup_for_project(&self.info, variables, artifacts, answers)?; }
     
     Ok(action)
   }
@@ -288,9 +363,10 @@ impl DescribedAction {
     action: &ProjectCleanAction,
     variables: &[Variable],
     artifacts: &[String],
+    answers: Option<&WithPipelineAnswers>,
   ) -> anyhow::Result<ProjectCleanAction> {
     let mut action = action.clone();
-    for cmd in &mut action.additional_commands { *cmd = cmd.prompt_setup_for_project(&self.info, variables, artifacts)?; }
+    for cmd in &mut action.additional_commands { *cmd = cmd.prompt_setup_for_project(&self.info, variables, artifacts, answers)?; }
     Ok(action)
   }
   
@@ -300,22 +376,24 @@ impl DescribedAction {
     targets: &[TargetDescription],
     variables: &[Variable],
     artifacts: &[String],
+    answers: Option<&WithPipelineAnswers>,
   ) -> anyhow::Result<PackAction> {
     let mut action = action.clone();
     
     if
       action.target.as_ref().is_some_and(|t| !targets.contains(t)) &&
-      !inquire::Confirm::new(
+      !self.use_incompatible(
         &i18n::ACTION_COMPAT_TARGETS
           .replace("{1}", &info2str_simple(&self.info))
           .replace("{2}", &format!("{}", action.target.as_ref().unwrap()))
-          .replace("{3}", &format!("{:?}", targets.iter().map(TargetDescription::to_string).collect::<Vec<_>>()))
-      ).prompt()?
+          .replace("{3}", &format!("{:?}", targets.iter().map(TargetDescription::to_string).collect::<Vec<_>>())),
+        answers,
+      )?
     {
       return Ok(PackAction::default())
     }
     
-    for cmd in &mut action.commands { *cmd = cmd.prompt_setup_for_project(&self.info, variables, artifacts)?; }
+    for cmd in &mut action.commands { *cmd = cmd.prompt_setup_for_project(&self.info, variables, artifacts, answers)?; }
     Ok(action)
   }
   
@@ -325,21 +403,23 @@ impl DescribedAction {
     deploy_toolkit: &Option<String>,
     variables: &[Variable],
     artifacts: &[String],
+    answers: Option<&WithPipelineAnswers>,
   ) -> anyhow::Result<DeployAction> {
     let mut action = action.clone();
     if
       action.deploy_toolkit.as_ref().is_some_and(|l| deploy_toolkit.as_ref().is_some_and(|r| l.as_str() != r.as_str())) &&
-      !inquire::Confirm::new(
+      !self.use_incompatible(
         &i18n::ACTION_COMPAT_DEPL_TOOLKIT
           .replace("{1}", &info2str_simple(&self.info))
           .replace("{2}", action.deploy_toolkit.as_ref().unwrap())
-          .replace("{3}", deploy_toolkit.as_ref().unwrap())
-      ).prompt()?
+          .replace("{3}", deploy_toolkit.as_ref().unwrap()),
+        answers,
+      )?
     {
       return Ok(DeployAction::default())
     }
     
-    for cmd in &mut action.commands { *cmd = cmd.prompt_setup_for_project(&self.info, variables, artifacts)?; }
+    for cmd in &mut action.commands { *cmd = cmd.prompt_setup_for_project(&self.info, variables, artifacts, answers)?; }
     
     Ok(action)
   }
@@ -349,10 +429,11 @@ impl DescribedAction {
     action: &ObserveAction,
     variables: &[Variable],
     artifacts: &[String],
+    answers: Option<&WithPipelineAnswers>,
   ) -> anyhow::Result<ObserveAction> {
     let mut action = action.clone();
     
-    action.command = action.command.prompt_setup_for_project(&self.info, variables, artifacts)?;
+    action.command = action.command.prompt_setup_for_project(&self.info, variables, artifacts, answers)?;
     
     Ok(action)
   }
@@ -364,22 +445,23 @@ impl DescribedAction {
     targets: &[TargetDescription],
     variables: &[Variable],
     artifacts: &[String],
+    answers: Option<&WithPipelineAnswers>,
   ) -> anyhow::Result<Self> {
     let action = match &self.action {
-      Action::Custom(cmd) => Action::Custom(cmd.prompt_setup_for_project(&self.info, variables, artifacts)?),
-      Action::Check(cmd) => Action::Check(cmd.prompt_setup_for_project(&self.info, variables, artifacts)?),
-      Action::PreBuild(pb_action) => Action::PreBuild(self.setup_buildlike_action(pb_action, langs, variables, artifacts)?),
-      Action::Build(b_action) => Action::Build(self.setup_buildlike_action(b_action, langs, variables, artifacts)?),
-      Action::PostBuild(pb_action) => Action::PostBuild(self.setup_buildlike_action(pb_action, langs, variables, artifacts)?),
-      Action::Test(t_action) => Action::Test(self.setup_buildlike_action(t_action, langs, variables, artifacts)?),
-      Action::ProjectClean(pc_action) => Action::ProjectClean(self.setup_projectclean_action(pc_action, variables, artifacts)?),
-      Action::Pack(p_action) => Action::Pack(self.setup_packlike_action(p_action, targets, variables, artifacts)?),
-      Action::Deliver(p_action) => Action::Deliver(self.setup_packlike_action(p_action, targets, variables, artifacts)?),
-      Action::Install(p_action) => Action::Install(self.setup_packlike_action(p_action, targets, variables, artifacts)?),
-      Action::ConfigureDeploy(cd_action) => Action::ConfigureDeploy(self.setup_deploylike_action(cd_action, deploy_toolkit, variables, artifacts)?),
-      Action::Deploy(d_action) => Action::Deploy(self.setup_deploylike_action(d_action, deploy_toolkit, variables, artifacts)?),
-      Action::PostDeploy(pd_action) => Action::PostDeploy(self.setup_deploylike_action(pd_action, deploy_toolkit, variables, artifacts)?),
-      Action::Observe(o_action) => Action::Observe(self.setup_observe_action(o_action, variables, artifacts)?),
+      Action::Custom(cmd) => Action::Custom(cmd.prompt_setup_for_project(&self.info, variables, artifacts, answers)?),
+      Action::Check(cmd) => Action::Check(cmd.prompt_setup_for_project(&self.info, variables, artifacts, answers)?),
+      Action::PreBuild(pb_action) => Action::PreBuild(self.setup_buildlike_action(pb_action, langs, variables, artifacts, answers)?),
+      Action::Build(b_action) => Action::Build(self.setup_buildlike_action(b_action, langs, variables, artifacts, answers)?),
+      Action::PostBuild(pb_action) => Action::PostBuild(self.setup_buildlike_action(pb_action, langs, variables, artifacts, answers)?),
+      Action::Test(t_action) => Action::Test(self.setup_buildlike_action(t_action, langs, variables, artifacts, answers)?),
+      Action::ProjectClean(pc_action) => Action::ProjectClean(self.setup_projectclean_action(pc_action, variables, artifacts, answers)?),
+      Action::Pack(p_action) => Action::Pack(self.setup_packlike_action(p_action, targets, variables, artifacts, answers)?),
+      Action::Deliver(p_action) => Action::Deliver(self.setup_packlike_action(p_action, targets, variables, artifacts, answers)?),
+      Action::Install(p_action) => Action::Install(self.setup_packlike_action(p_action, targets, variables, artifacts, answers)?),
+      Action::ConfigureDeploy(cd_action) => Action::ConfigureDeploy(self.setup_deploylike_action(cd_action, deploy_toolkit, variables, artifacts, answers)?),
+      Action::Deploy(d_action) => Action::Deploy(self.setup_deploylike_action(d_action, deploy_toolkit, variables, artifacts, answers)?),
+      Action::PostDeploy(pd_action) => Action::PostDeploy(self.setup_deploylike_action(pd_action, deploy_toolkit, variables, artifacts, answers)?),
+      Action::Observe(o_action) => Action::Observe(self.setup_observe_action(o_action, variables, artifacts, answers)?),
       Action::Interrupt | Action::ForceArtifactsEnplace => self.action.clone(),
     };
     
@@ -2,6 +2,7 @@ use colored::Colorize;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 
+use crate::configs::WithPipelineAnswers;
 use crate::entities::{
   environment::BuildEnvironment,
   custom_command::CustomCommand,
@@ -78,9 +79,10 @@ impl CheckAction {
     info: &ActionInfo,
     variables: &[Variable],
     artifacts: &[String],
+    answers: Option<&WithPipelineAnswers>,
   ) -> anyhow::Result<Self> {
     let mut r = self.clone();
-    r.command = r.command.prompt_setup_for_project(info, variables, artifacts)?;
+    r.command = r.command.prompt_setup_for_project(info, variables, artifacts, answers)?;
     Ok(r)
   }
 }
@@ -89,13 +91,16 @@ impl Execute for CheckAction {
   fn execute(&self, env: BuildEnvironment) -> anyhow::Result<(bool, Vec<String>)> {
     let mut output = vec![];
     
-    let (status, command_out) = self.command.execute(env)?;
+    let (status, command_out, captured) = self.command.run(env)?;
     if !status && !self.command.ignore_fails {
       return Ok((false, command_out))
     }
+    output.extend(command_out);
+    // При пробном запуске вывода команды нет, и проверять его нечего
+    if env.dry_run { return Ok((true, output)) }
     
     if let Some(re) = &self.success_when_found {
-      let text = command_out.join("\n");
+      let text = captured.join("\n");
       if re.is_match(text.as_str()) { output.push(format!("{} `{}` {}!", i18n::PATTERN, re.as_str().green(), i18n::FOUND)); }
       else {
         output.push(format!("{} `{}` {}!", i18n::PATTERN, re.as_str().green(), i18n::NOT_FOUND));
@@ -104,7 +109,7 @@ impl Execute for CheckAction {
     }
     
     if let Some(re) = &self.success_when_not_found {
-      let text = command_out.join("\n");
+      let text = captured.join("\n");
       if !re.is_match(text.as_str()) { output.push(format!("{} `{}` {}!", i18n::PATTERN, re.as_str().green(), i18n::NOT_FOUND)); }
       else {
         output.push(format!("{} `{}` {}!", i18n::PATTERN, re.as_str().green(), i18n::FOUND));
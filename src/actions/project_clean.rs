@@ -3,6 +3,7 @@ use serde::{Deserialize, Serialize};
 use crate::entities::environment::BuildEnvironment;
 use crate::entities::custom_command::CustomCommand;
 use crate::entities::traits::Execute;
+use crate::i18n;
 
 #[derive(Deserialize, Serialize, PartialEq, Clone, Debug)]
 pub(crate) struct ProjectCleanAction {
@@ -15,6 +16,10 @@ impl Execute for ProjectCleanAction {
     let mut total_output = vec![];
     
     for entity in &self.to_remove {
+      if env.dry_run {
+        env.println(i18n::DRY_RUN_REMOVE.replace("{}", &env.build_dir.join(entity).to_string_lossy()));
+        continue
+      }
       crate::rw::remove_all(env.build_dir.join(entity))?;
     }
     
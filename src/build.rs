@@ -1,16 +1,24 @@
+use chrono::{DateTime, Local};
 use colored::Colorize;
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
 use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use uuid::Uuid;
 
-use crate::{CACHE_DIR, ARTIFACTS_DIR, BUILD_CACHE_LIST};
-use crate::entities::environment::BuildEnvironment;
+use crate::{CACHE_DIR, ARTIFACTS_DIR, BUILD_CACHE_LIST, PROJECT_CONF, EXIT_ACTION_FAILURE, EXIT_USER_ABORT};
+use crate::entities::{custom_command::ExitCodes, environment::BuildEnvironment, variables::{SecretsMask, VarsCache}};
 use crate::cmd::{BuildArgs, CleanArgs};
 use crate::configs::DeployerProjectOptions;
+use crate::history;
 use crate::i18n;
-use crate::pipelines::{execute_pipeline, DescribedPipeline};
-use crate::rw::{copy_all, write, symlink, log};
-use crate::utils::get_current_working_dir;
+use crate::interrupt;
+use crate::pipelines::{execute_pipeline, join_errors, ActionStatus, BuildSource, DescribedPipeline, PipelineOutcome};
+use crate::report::write_reports;
+use crate::rw::{copy_all, read_checked, remove_path, sync_all, write, symlink, log, IgnoreRules, LogFormat, ARTIFACTS_IGNORE_FILES, PROJECT_IGNORE_FILES};
+use crate::secrets::ProjectSecrets;
+use crate::utils::{build_relative_path, format_table, get_current_working_dir, ConfigError};
 
 /// Список всех билдов в системе
 #[derive(Deserialize, Serialize, Default)]
@@ -33,29 +41,70 @@ pub(crate) struct BuildStats {
   pub(crate) exclusive_tag: Option<String>,
   /// Путь сборки
   pub(crate) folder: PathBuf,
+  /// Коммит, из которого подготовлена папка сборки (если папка сборки - `git worktree`)
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub(crate) commit: Option<String>,
+  /// Время последнего использования папки сборки
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub(crate) last_used: Option<DateTime<Local>>,
+  /// Статусы Действий при последнем запуске Пайплайнов в этой папке (по заголовкам Пайплайнов)
+  #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+  pub(crate) last_runs: BTreeMap<String, Vec<ActionRun>>,
+}
+
+/// Статус Действия при последнем запуске Пайплайна
+#[derive(Deserialize, Serialize, Clone)]
+pub(crate) struct ActionRun {
+  /// Заголовок Действия
+  pub(crate) title: String,
+  /// Статус выполнения; отсутствует, если Действие не выполнялось
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub(crate) status: Option<ActionStatus>,
 }
 
 impl BuildStats {
   pub(crate) fn works_with(&self, pipeline: &DescribedPipeline) -> bool {
-    self.exclusive_tag.as_ref().is_some_and(|a| pipeline.exclusive_exec_tag.as_ref().is_some_and(|b| a.as_str().eq(b.as_str()))) ||
-    (self.exclusive_tag.is_none() && pipeline.exclusive_exec_tag.is_none())
+    let same_tag = self.exclusive_tag.as_ref().is_some_and(|a| pipeline.exclusive_exec_tag.as_ref().is_some_and(|b| a.as_str().eq(b.as_str()))) ||
+      (self.exclusive_tag.is_none() && pipeline.exclusive_exec_tag.is_none());
+    // Папки-worktree и папки с копией проекта не используются вместо друг друга
+    same_tag && self.commit.is_some() == pipeline.uses_worktree()
+  }
+  
+  /// Статусы Действий последнего запуска Пайплайна, если не все его Действия были выполнены успешно
+  pub(crate) fn unfinished_run(&self, pipeline: &DescribedPipeline) -> Option<&Vec<ActionRun>> {
+    self.last_runs.get(&pipeline.title).filter(|runs| runs.iter().any(|r| r.status.is_none_or(|s| !s.is_ok())))
   }
 }
 
 pub(crate) fn enplace_artifacts(
   config: &DeployerProjectOptions,
   env: BuildEnvironment,
-  panic_when_not_found: bool,
+  fail_when_not_found: bool,
 ) -> anyhow::Result<()> {
+  if env.dry_run {
+    for (from, to) in &config.inplace_artifacts_into_project_root {
+      env.println(
+        i18n::DRY_RUN_COPY
+          .replace("{1}", &env.build_dir.join(from).to_string_lossy())
+          .replace("{2}", &env.artifacts_dir.join(to).to_string_lossy()),
+      );
+    }
+    return Ok(())
+  }
+  
   let mut ignore = vec![ARTIFACTS_DIR];
   ignore.extend_from_slice(&(config.cache_files.iter().map(|c| c.as_str()).collect::<Vec<_>>()));
+  let mut rules = IgnoreRules::new(&ignore, ARTIFACTS_IGNORE_FILES);
+  rules.enter(env.build_dir);
   
   for (from, to) in &config.inplace_artifacts_into_project_root {
     let artifact_path = env.build_dir.join(from);
     if !std::fs::exists(artifact_path.clone())? {
-      if panic_when_not_found { panic!("There is no `{:?}` artifact!", artifact_path); }
+      if fail_when_not_found {
+        return Err(ConfigError(format!("There is no `{}` artifact!", artifact_path.display())).into())
+      }
     } else if artifact_path.as_path().is_dir() || artifact_path.as_path().is_file() {
-      copy_all(artifact_path.as_path(), env.artifacts_dir.join(to).as_path(), &ignore)?;
+      copy_all(artifact_path.as_path(), env.artifacts_dir.join(to).as_path(), &mut rules)?;
     }
   }
   
@@ -78,7 +127,7 @@ fn prepare_build_folder(
   current_dir: &std::path::Path,
   cache_dir: &Path,
   args: &BuildArgs,
-) -> anyhow::Result<(PathBuf, bool)> {
+) -> anyhow::Result<(PathBuf, bool, Option<String>)> {
   let build_path = if let Some(build_at) = args.build_at.as_ref() {
     build_at.to_owned()
   } else {
@@ -95,14 +144,26 @@ fn prepare_build_folder(
       },
     };
     
-    let folder = match project_builds.builds.iter().rev().find(|b| b.works_with(selected_pipeline)) {
+    let last_build = project_builds.builds
+      .iter_mut()
+      .rev()
+      .find(|b| b.works_with(selected_pipeline) && (!args.resume || b.unfinished_run(selected_pipeline).is_some()));
+    
+    let folder = match last_build {
       Some(b_stats) if !args.fresh => {
+        b_stats.last_used = Some(Local::now());
         b_stats.folder.to_owned()
       },
       _ => {
         let uuid = format!("deploy-build-{}", Uuid::new_v4());
         let folder = build_path.join(uuid);
-        let b_stats = BuildStats { exclusive_tag: selected_pipeline.exclusive_exec_tag.clone(), folder: folder.to_owned(), };
+        let b_stats = BuildStats {
+          exclusive_tag: selected_pipeline.exclusive_exec_tag.clone(),
+          folder: folder.to_owned(),
+          commit: None,
+          last_used: Some(Local::now()),
+          last_runs: BTreeMap::new(),
+        };
         project_builds.builds.push(b_stats);
         folder.to_owned()
       },
@@ -114,12 +175,27 @@ fn prepare_build_folder(
   };
   
   let fresh = !build_path.exists() || args.fresh;
-  std::fs::create_dir_all(build_path.as_path()).unwrap_or_else(|_| panic!("Can't create `{:?}` folder!", build_path));
-  
-  let mut ignore = vec![ARTIFACTS_DIR, build_path.file_name().unwrap().to_str().unwrap()];
-  ignore.extend_from_slice(&config.cache_files.iter().map(|v| v.as_str()).collect::<Vec<_>>());
   
-  copy_all(get_current_working_dir().unwrap(), build_path.as_path(), &ignore)?;
+  let commit = match selected_pipeline.build_source {
+    Some(source @ (BuildSource::Worktree | BuildSource::WorktreeWithChanges)) => {
+      let commit = prepare_worktree(current_dir, &build_path, source == BuildSource::WorktreeWithChanges)?;
+      let b_stats = builds.projects
+        .iter_mut()
+        .find(|p| p.name.as_str().eq(config.project_name.as_str()))
+        .and_then(|p| p.builds.iter_mut().find(|b| b.folder.as_path().eq(build_path.as_path())));
+      if let Some(b_stats) = b_stats { b_stats.commit = Some(commit.to_owned()); }
+      Some(commit)
+    },
+    _ => {
+      std::fs::create_dir_all(build_path.as_path()).unwrap_or_else(|_| panic!("Can't create `{:?}` folder!", build_path));
+      
+      let mut ignore = vec![ARTIFACTS_DIR, build_path.file_name().unwrap().to_str().unwrap()];
+      ignore.extend_from_slice(&config.cache_files.iter().map(|v| v.as_str()).collect::<Vec<_>>());
+      
+      sync_all(get_current_working_dir().unwrap(), build_path.as_path(), &mut IgnoreRules::new(&ignore, PROJECT_IGNORE_FILES))?;
+      None
+    },
+  };
   write(cache_dir, BUILD_CACHE_LIST, &builds);
   
   if args.link_cache {
@@ -134,27 +210,135 @@ fn prepare_build_folder(
       copy_all(
         current_dir.join(cache_item.as_str()),
         build_path.join(cache_item.as_str()),
-        &[]
+        &mut IgnoreRules::new(&[], &[]),
       )?;
       log(format!("-> {}", cache_item.as_str()));
     }
   }
   
-  Ok((build_path, fresh))
+  Ok((build_path, fresh, commit))
+}
+
+/// Подготавливает папку сборки как `git worktree` текущего коммита проекта; возвращает хэш коммита.
+/// 
+/// Если папка сборки уже является `git worktree`, в ней восстанавливается состояние коммита; файлы, не отслеживаемые git
+/// (например, результаты прошлых сборок), сохраняются - кроме новых файлов, перенесённых прошлой сборкой. С `include_changes`
+/// в папку сборки переносятся и незакоммиченные изменения: изменения отслеживаемых файлов - в виде патча, новые файлы
+/// (кроме игнорируемых git) - копированием.
+fn prepare_worktree(
+  current_dir: &Path,
+  build_path: &Path,
+  include_changes: bool,
+) -> anyhow::Result<String> {
+  let commit = String::from_utf8(git(current_dir, &["rev-parse", "HEAD"], None)?)?.trim().to_owned();
+  
+  if build_path.join(".git").is_file() {
+    // Новые файлы, перенесённые прошлой сборкой вместе с незакоммиченными изменениями, git не отслеживает и при
+    // переключении коммита не удаляет - они удаляются явно, чтобы в сборку не попали файлы, которых уже нет
+    let transferred = read_checked::<Vec<String>>(build_path.join(WORKTREE_CHANGES_MANIFEST)).unwrap_or_default();
+    for file in &transferred { remove_path(&build_path.join(build_relative_path(file)?))?; }
+    git(build_path, &["checkout", "--force", "--detach", &commit], None)?;
+  } else if build_path.exists() && std::fs::read_dir(build_path)?.next().is_some() {
+    return Err(anyhow::anyhow!("Can't create a git worktree in `{}`: the folder isn't empty!", build_path.display()))
+  } else {
+    // Папки удалённых сборок могли остаться зарегистрированными в репозитории
+    git(current_dir, &["worktree", "prune"], None)?;
+    git(current_dir, &["worktree", "add", "--detach", &build_path.to_string_lossy(), &commit], None)?;
+  }
+  log(format!("-> {}", commit));
+  
+  let mut transferred = vec![];
+  if include_changes {
+    let split = |output: Vec<u8>| output
+      .split(|b| *b == 0)
+      .filter(|f| !f.is_empty())
+      .map(|f| String::from_utf8_lossy(f).to_string())
+      .collect::<Vec<_>>();
+    
+    // Файлы, добавленные в индекс, но ещё не закоммиченные, появятся в папке сборки из патча
+    transferred.extend(split(git(current_dir, &["diff", "HEAD", "--name-only", "--diff-filter=A", "-z"], None)?));
+    let patch = git(current_dir, &["diff", "HEAD", "--binary"], None)?;
+    if !patch.is_empty() { git(build_path, &["apply", "--binary", "-"], Some(&patch))?; }
+    
+    for file in split(git(current_dir, &["ls-files", "--others", "--exclude-standard", "-z"], None)?) {
+      log(format!("-> {}", file));
+      copy_all(current_dir.join(&file), build_path.join(&file), &mut IgnoreRules::new(&[], &[]))?;
+      transferred.push(file);
+    }
+  }
+  write(build_path, WORKTREE_CHANGES_MANIFEST, &transferred);
+  
+  if !build_path.join(PROJECT_CONF).exists() { symlink(current_dir.join(PROJECT_CONF), build_path.join(PROJECT_CONF)); }
+  
+  Ok(commit)
+}
+
+/// Список новых файлов, перенесённых в папку-worktree вместе с незакоммиченными изменениями.
+const WORKTREE_CHANGES_MANIFEST: &str = ".deploy-worktree-changes.json";
+
+/// Выполняет команду git в указанной папке, передавая ей `input`; возвращает вывод команды.
+fn git(dir: &Path, args: &[&str], input: Option<&[u8]>) -> anyhow::Result<Vec<u8>> {
+  use std::io::Write;
+  use std::process::{Command, Stdio};
+  
+  let mut child = Command::new("git")
+    .args(args)
+    .current_dir(dir)
+    .stdin(if input.is_some() { Stdio::piped() } else { Stdio::null() })
+    .stdout(Stdio::piped())
+    .stderr(Stdio::piped())
+    .spawn()
+    .map_err(|e| anyhow::anyhow!("Can't execute git due to: {}", e))?;
+  if let Some(input) = input && let Some(mut stdin) = child.stdin.take() { stdin.write_all(input)?; }
+  
+  let output = child.wait_with_output()?;
+  if !output.status.success() {
+    return Err(anyhow::anyhow!("`git {}` failed: {}", args.join(" "), String::from_utf8_lossy(&output.stderr).trim()))
+  }
+  Ok(output.stdout)
+}
+
+/// Папка сборки, которая будет использована Пайплайном, - без её создания и изменения списка сборок (`--dry-run`).
+fn planned_build_folder(
+  config: &DeployerProjectOptions,
+  builds: &Builds,
+  selected_pipeline: &DescribedPipeline,
+  cache_dir: &Path,
+  args: &BuildArgs,
+) -> (PathBuf, bool) {
+  let folder = match args.build_at.as_ref() {
+    Some(build_at) => Some(build_at.to_owned()),
+    None if args.fresh => None,
+    None => builds.projects
+      .iter()
+      .find(|p| p.name.as_str().eq(config.project_name.as_str()))
+      .and_then(|p| p.builds.iter().rev().find(|b| b.works_with(selected_pipeline) && (!args.resume || b.unfinished_run(selected_pipeline).is_some())))
+      .map(|b| b.folder.to_owned()),
+  };
+  
+  match folder {
+    Some(folder) => {
+      let fresh = !folder.exists() || args.fresh;
+      (folder, fresh)
+    },
+    None => (cache_dir.join(CACHE_DIR).join("deploy-build-<uuid>"), true),
+  }
 }
 
 pub(crate) fn build(
   config: &mut DeployerProjectOptions,
   builds: &mut Builds,
   cache_dir: &Path,
+  secrets: &ProjectSecrets,
+  log_format: LogFormat,
   args: &BuildArgs,
-) -> anyhow::Result<()> {
-  if *config == Default::default() { panic!("{}", i18n::CFG_INVALID); }
+) -> anyhow::Result<Vec<PipelineOutcome>> {
+  if *config == Default::default() { return Err(ConfigError(i18n::CFG_INVALID.to_string()).into()) }
   
-  if args.link_cache && args.copy_cache { panic!(
+  if args.link_cache && args.copy_cache { return Err(ConfigError(format!(
     "Select only one option from `{}` and `{}`. See help via `{}`.", "c".green(), "C".green(), "deployer build -h".green()
-  ); }
-  if (args.fresh || args.link_cache || args.copy_cache || args.build_at.is_some()) && args.current { panic!(
+  )).into()) }
+  if (args.fresh || args.link_cache || args.copy_cache || args.build_at.is_some()) && args.current { return Err(ConfigError(format!(
     "Select either `{}` or `{}`/{}`/`{}`/`{}` options. See help via `{}`.",
     "o".green(),
     "j".green(),
@@ -162,74 +346,378 @@ pub(crate) fn build(
     "c".green(),
     "C".green(),
     "deployer build -h".green(),
-  ); }
-  if args.silent && args.no_pipe { panic!(
+  )).into()) }
+  if args.silent && args.no_pipe { return Err(ConfigError(format!(
     "Select only one option from `{}` and `{}`. See help via `{}`.", "s".green(), "t".green(), "deployer build -h".green()
-  ); }
-
+  )).into()) }
+  if args.jobs > 1 && args.no_pipe { return Err(ConfigError(format!(
+    "Option `{}` can't be used with concurrent Pipelines (`{}`). See help via `{}`.", "t".green(), "jobs".green(), "deployer build -h".green()
+  )).into()) }
+  if [args.resume, args.from.is_some(), !args.only.is_empty()].into_iter().filter(|v| *v).count() > 1 { return Err(ConfigError(format!(
+    "Select only one option from `{}`, `{}` and `{}`. See help via `{}`.", "resume".green(), "from".green(), "only".green(), "deployer build -h".green()
+  )).into()) }
+  if args.silent && args.dry_run { return Err(ConfigError(format!(
+    "Select only one option from `{}` and `{}`. See help via `{}`.", "s".green(), "dry-run".green(), "deployer build -h".green()
+  )).into()) }
+  if args.resume && (args.current || args.build_at.is_some() || args.fresh) { return Err(ConfigError(format!(
+    "Option `{}` can't be used with `{}`/`{}`/`{}` options. See help via `{}`.",
+    "resume".green(),
+    "j".green(),
+    "o".green(),
+    "f".green(),
+    "deployer build -h".green(),
+  )).into()) }
+  
+  interrupt::handle_gracefully();
+  
   let curr_dir = std::env::current_dir().expect("Can't get current dir!");
-  let artifacts_dir = prepare_artifacts_folder(&curr_dir)?;
+  let artifacts_dir = if args.dry_run { curr_dir.join(ARTIFACTS_DIR) } else { prepare_artifacts_folder(&curr_dir)? };
+  
+  let selected_pipelines = select_pipelines(config, args)?;
+  for pipeline in &selected_pipelines {
+    pipeline.validate().map_err(|e| ConfigError(e.to_string()))?;
+    selected_actions(pipeline, args)?;
+    if args.resume { resumed_actions(config, builds, pipeline)?; }
+  }
+  let config = &*config;
+  let builds = Mutex::new(builds);
+  
+  // Итог Пайплайна возвращается и при ошибке Деплойера, если Пайплайн успел запуститься
+  let run = |pipeline: &DescribedPipeline, output_prefix: Option<&str>| -> (Option<PipelineOutcome>, anyhow::Result<()>) {
+    let prepared = (|| {
+      let mut preset = selected_actions(pipeline, args)?;
+      let (build_path, new_build, commit) = if args.current {
+        (curr_dir.clone(), false, None)
+      } else {
+        let mut builds = builds.lock().map_err(|_| anyhow::anyhow!("Builds' list is poisoned!"))?;
+        if args.resume { preset = resumed_actions(config, &builds, pipeline)?; }
+        if args.dry_run {
+          let (build_path, new_build) = planned_build_folder(config, &builds, pipeline, cache_dir, args);
+          (build_path, new_build, None)
+        } else {
+          prepare_build_folder(config, &mut builds, pipeline, &curr_dir, cache_dir, args)?
+        }
+      };
+      anyhow::Ok((preset, build_path, new_build, commit))
+    })();
+    let (preset, build_path, new_build, commit) = match prepared {
+      Ok(prepared) => prepared,
+      Err(e) => return (None, Err(e)),
+    };
+    
+    let vars_cache = VarsCache::default();
+    let secrets_mask = SecretsMask::default();
+    let exit_codes = ExitCodes::default();
+    let env = BuildEnvironment {
+      build_dir: &build_path,
+      project_dir: &curr_dir,
+      cache_dir,
+      artifacts_dir: &artifacts_dir,
+      new_build,
+      silent_build: args.silent,
+      no_pipe: args.no_pipe,
+      dry_run: args.dry_run,
+      vars_cache: &vars_cache,
+      secrets_mask: &secrets_mask,
+      exit_codes: &exit_codes,
+      log_format: args.log_format.unwrap_or(log_format),
+      log: None,
+      commit: commit.as_deref(),
+      default_timeout: None,
+      secrets,
+      output_prefix,
+      injected_placeholders: &[],
+    };
+    
+    if args.dry_run {
+      let folder = build_path.to_string_lossy();
+      env.println(if new_build { i18n::DRY_RUN_NEW_BUILD_FOLDER.replace("{}", &folder) } else { i18n::DRY_RUN_BUILD_FOLDER.replace("{}", &folder) });
+    }
+    
+    let (mut outcome, res) = execute_pipeline(config, env, pipeline, &preset);
+    
+    if !args.dry_run {
+      match builds.lock() {
+        Ok(mut builds) => if record_run(config, &mut builds, pipeline, &build_path, &outcome) { write(cache_dir, BUILD_CACHE_LIST, &*builds); },
+        Err(_) => return (Some(outcome), Err(anyhow::anyhow!("Builds' list is poisoned!"))),
+      }
+    }
+    if res.is_err() { return (Some(outcome), res) }
+    
+    if outcome.success() {
+      if let Err(e) = enplace_artifacts(config, env, true) {
+        // Пайплайн, не создавший объявленные артефакты, в истории считается неуспешным
+        if !args.dry_run { history::record_finish(cache_dir, &outcome.run_id, ActionStatus::Failed, outcome.elapsed); }
+        return (Some(outcome), Err(e))
+      }
+      if !args.dry_run {
+        outcome.artifacts = config.inplace_artifacts_into_project_root
+          .iter()
+          .map(|(_, to)| artifacts_dir.join(to))
+          .filter(|path| path.exists())
+          .collect();
+      }
+      if args.dry_run {
+        env.println(i18n::DRY_RUN_DONE.replace("{}", &outcome.title));
+      } else if !args.silent {
+        env.println(i18n::PIPELINE_DONE.replace("{1}", &outcome.title).replace("{2}", &format!("{:.2?}", outcome.elapsed).green()));
+      }
+    } else if !args.silent && let Some(failed) = outcome.failed_action.and_then(|i| outcome.actions.get(i)) {
+      env.println(
+        i18n::PIPELINE_FAILED
+          .replace("{1}", &outcome.title)
+          .replace("{2}", &failed.title.blue().italic())
+          .replace("{3}", &format!("{:.2?}", failed.elapsed).red()),
+      );
+    }
+    
+    (Some(outcome), Ok(()))
+  };
+  
+  if args.jobs == 1 {
+    let mut outcomes = vec![];
+    let mut error = None;
+    for pipeline in selected_pipelines {
+      let (outcome, res) = run(pipeline, None);
+      let aborted = outcome.as_ref().is_some_and(|o| o.aborted());
+      outcomes.extend(outcome);
+      if let Err(e) = res {
+        error = Some(e);
+        break
+      }
+      if aborted { break }
+    }
+    // Отчёты о выполненных Пайплайнах записываются и при ошибке Деплойера
+    let reported = write_reports(config, &outcomes, args);
+    if let Some(e) = error { return Err(e) }
+    reported?;
+    return Ok(outcomes)
+  }
+  
+  let lanes = split_into_lanes(selected_pipelines, args);
+  let prefixes = lanes.iter().flatten().map(|(i, p)| (*i, format!("[{}]", p.title).blue().bold().to_string())).collect::<HashMap<_, _>>();
+  let next_lane = AtomicUsize::new(0);
+  let stop = AtomicBool::new(false);
+  let results = Mutex::new(vec![]);
+  
+  std::thread::scope(|s| {
+    for _ in 0..(args.jobs as usize).min(lanes.len()) {
+      s.spawn(|| {
+        while let Some(lane) = lanes.get(next_lane.fetch_add(1, Ordering::SeqCst)) {
+          for (i, pipeline) in lane {
+            if stop.load(Ordering::SeqCst) || interrupt::interrupted() { return }
+            
+            let (outcome, res) = run(pipeline, prefixes.get(i).map(|p| p.as_str()));
+            // Пайплайны не запускаются после прерывания или ошибки Деплойера
+            if res.is_err() || outcome.as_ref().is_some_and(|outcome| outcome.aborted()) { stop.store(true, Ordering::SeqCst); }
+            results.lock().unwrap_or_else(std::sync::PoisonError::into_inner).push((*i, outcome, res));
+          }
+        }
+      });
+    }
+  });
+  
+  let mut results = results.into_inner().unwrap_or_else(std::sync::PoisonError::into_inner);
+  results.sort_by_key(|(i, _, _)| *i);
+  let mut outcomes = vec![];
+  let mut errors = vec![];
+  for (_, outcome, res) in results {
+    outcomes.extend(outcome);
+    errors.extend(res.err());
+  }
+  
+  // Итоги и отчёты о выполненных Пайплайнах выводятся и при ошибке Деплойера в другой очереди
+  if !args.silent { print_summary(&outcomes); }
+  let reported = write_reports(config, &outcomes, args);
+  join_errors(errors)?;
+  reported?;
+  
+  Ok(outcomes)
+}
+
+/// Очередь Пайплайнов (с их порядковыми номерами), выполняемых последовательно.
+type Lane<'a> = Vec<(usize, &'a DescribedPipeline)>;
+
+/// Разбивает Пайплайны на очереди: Пайплайны одной очереди используют одну папку сборки и выполняются
+/// последовательно, разные очереди могут выполняться параллельно.
+fn split_into_lanes<'a>(
+  pipelines: Vec<&'a DescribedPipeline>,
+  args: &BuildArgs,
+) -> Vec<Lane<'a>> {
+  let mut lanes: Vec<(Option<String>, Lane)> = vec![];
+  
+  for (i, pipeline) in pipelines.into_iter().enumerate() {
+    // Сборка в текущей или указанной папке - одна папка для всех Пайплайнов;
+    // при новой сборке каждый Пайплайн получает свою папку
+    if args.current || args.build_at.is_some() {
+      if lanes.is_empty() { lanes.push((None, vec![])); }
+      lanes[0].1.push((i, pipeline));
+    } else if args.fresh {
+      lanes.push((None, vec![(i, pipeline)]));
+    } else {
+      match lanes.iter_mut().find(|(tag, _)| *tag == pipeline.exclusive_exec_tag) {
+        Some((_, lane)) => lane.push((i, pipeline)),
+        None => lanes.push((pipeline.exclusive_exec_tag.clone(), vec![(i, pipeline)])),
+      }
+    }
+  }
+  
+  lanes.into_iter().map(|(_, lane)| lane).collect()
+}
+
+/// Выводит итоговую таблицу выполнения Пайплайнов.
+fn print_summary(outcomes: &[PipelineOutcome]) {
+  let rows = outcomes
+    .iter()
+    .map(|o| {
+      let status = o.status();
+      let failed_action = o.failed_action.and_then(|i| o.actions.get(i)).map(|a| a.title.as_str()).unwrap_or_default();
+      vec![o.title.to_owned(), status.describe().trim().to_owned(), failed_action.to_owned(), format!("{:.2?}", o.elapsed)]
+    })
+    .collect::<Vec<_>>();
+  
+  let header = [i18n::SUMMARY_PIPELINE, i18n::SUMMARY_STATUS, i18n::SUMMARY_ACTION, i18n::SUMMARY_ELAPSED];
+  let (header, separator, lines) = format_table(&header, &rows);
+  
+  println!();
+  println!("{}", header.bold());
+  println!("{}", separator);
+  for (line, outcome) in lines.iter().zip(outcomes) {
+    if outcome.success() { println!("{}", line.green()); } else { println!("{}", line.red()); }
+  }
+}
 
+/// Выбирает Пайплайны для выполнения: указанные пользователем или Пайплайны по умолчанию.
+fn select_pipelines<'a>(
+  config: &'a DeployerProjectOptions,
+  args: &BuildArgs,
+) -> anyhow::Result<Vec<&'a DescribedPipeline>> {
   if args.pipeline_tags.is_empty() {
     if config.pipelines.is_empty() {
-      panic!("The pipelines' list is empty! Check the config file for errors.");
+      return Err(ConfigError("The pipelines' list is empty! Check the config file for errors.".to_string()).into())
     }
+    
+    let defaults = config.pipelines.iter().filter(|p| p.default.is_some_and(|v| v)).collect::<Vec<_>>();
+    if defaults.is_empty() {
+      return Err(ConfigError("There is no default Pipelines! Please, specify at least one to execute.".to_string()).into())
+    }
+    
+    return Ok(defaults)
+  }
+  
+  let mut selected = vec![];
+  for pipeline_tag in &args.pipeline_tags {
+    match config.pipelines.iter().find(|p| p.title.as_str().eq(pipeline_tag)) {
+      Some(pipeline) => selected.push(pipeline),
+      None => return Err(ConfigError(format!(
+        "There is no such Pipeline `{}` set up for this project. Maybe, you've forgotten set up this Pipeline for project via `{}`?",
+        pipeline_tag.green(),
+        "deployer with {pipeline-short-name-and-ver}".green(),
+      )).into()),
+    }
+  }
+  
+  Ok(selected)
+}
 
-    let cntr = config.pipelines.iter().filter(|p| p.default.is_some_and(|v| v)).count();
-    if cntr == 0 { panic!("There is no default Pipelines! Please, specify at least one to execute."); }
+/// Действия, выбранные пользователем (`--from`, `--only`): для невыбранных Действий указывается статус `NotSelected`.
+fn selected_actions(
+  pipeline: &DescribedPipeline,
+  args: &BuildArgs,
+) -> anyhow::Result<Vec<Option<ActionStatus>>> {
+  let find = |title: &str| -> anyhow::Result<usize> {
+    let mut found = pipeline.actions.iter().enumerate().filter(|(_, a)| a.title.eq(title)).map(|(i, _)| i);
+    match (found.next(), found.next()) {
+      (Some(i), None) => Ok(i),
+      (None, _) => Err(ConfigError(format!("There is no `{}` Action in the `{}` Pipeline!", title.green(), pipeline.title)).into()),
+      (Some(_), Some(_)) => Err(ConfigError(format!(
+        "There are several `{}` Actions in the `{}` Pipeline! Give them unique titles.", title.green(), pipeline.title,
+      )).into()),
+    }
+  };
+  
+  if let Some(from) = &args.from {
+    let from = find(from)?;
+    return Ok((0..pipeline.actions.len()).map(|i| if i < from { Some(ActionStatus::NotSelected) } else { None }).collect())
+  }
+  
+  if !args.only.is_empty() {
+    let mut preset = vec![Some(ActionStatus::NotSelected); pipeline.actions.len()];
+    for title in &args.only { preset[find(title)?] = None; }
+    return Ok(preset)
+  }
+  
+  Ok(vec![])
+}
 
-    for pipeline in config.pipelines.iter().filter(|p| p.default.is_some_and(|v| v)) {
-      let (build_path, new_build) = if args.current {
-        (curr_dir.clone(), false)
-      } else {
-        prepare_build_folder(config, builds, pipeline, &curr_dir, cache_dir, args)?
-      };
+/// Действия, успешно выполненные при последнем незавершённом запуске Пайплайна (`--resume`).
+fn resumed_actions(
+  config: &DeployerProjectOptions,
+  builds: &Builds,
+  pipeline: &DescribedPipeline,
+) -> anyhow::Result<Vec<Option<ActionStatus>>> {
+  let runs = builds.projects
+    .iter()
+    .find(|p| p.name.as_str().eq(config.project_name.as_str()))
+    .and_then(|p| p.builds.iter().rev().find_map(|b| b.unfinished_run(pipeline).filter(|_| b.works_with(pipeline))));
+  
+  let Some(runs) = runs else {
+    return Err(ConfigError(format!("There is no unfinished run of the `{}` Pipeline to resume!", pipeline.title.green())).into())
+  };
+  if !runs.iter().map(|r| r.title.as_str()).eq(pipeline.actions.iter().map(|a| a.title.as_str())) {
+    return Err(ConfigError(format!(
+      "The `{}` Pipeline's Actions have been changed since its last run, so it can't be resumed. Use `{}` or `{}` instead.",
+      pipeline.title.green(),
+      "from".green(),
+      "only".green(),
+    )).into())
+  }
+  
+  Ok(runs.iter().map(|r| matches!(r.status, Some(ActionStatus::Done | ActionStatus::Cached)).then_some(ActionStatus::PreviouslyDone)).collect())
+}
 
-      let env = BuildEnvironment {
-        build_dir: &build_path,
-        cache_dir,
-        artifacts_dir: &artifacts_dir,
-        new_build,
-        silent_build: args.silent,
-        no_pipe: args.no_pipe,
+/// Сохраняет статусы Действий Пайплайна в информации о папке сборки, чтобы запуск можно было возобновить.
+/// 
+/// Для невыбранных пользователем Действий сохраняются статусы предыдущего запуска.
+fn record_run(
+  config: &DeployerProjectOptions,
+  builds: &mut Builds,
+  pipeline: &DescribedPipeline,
+  build_path: &Path,
+  outcome: &PipelineOutcome,
+) -> bool {
+  let b_stats = builds.projects
+    .iter_mut()
+    .find(|p| p.name.as_str().eq(config.project_name.as_str()))
+    .and_then(|p| p.builds.iter_mut().find(|b| b.folder.as_path().eq(build_path)));
+  let Some(b_stats) = b_stats else { return false };
+  
+  let previous = b_stats.last_runs
+    .remove(&pipeline.title)
+    .filter(|runs| runs.iter().map(|r| r.title.as_str()).eq(pipeline.actions.iter().map(|a| a.title.as_str())));
+  let runs = pipeline.actions
+    .iter()
+    .zip(&outcome.action_statuses)
+    .enumerate()
+    .map(|(i, (action, status))| {
+      let status = match status {
+        Some(ActionStatus::PreviouslyDone) => Some(ActionStatus::Done),
+        Some(ActionStatus::NotSelected) => previous.as_ref().and_then(|runs| runs[i].status),
+        status => *status,
       };
-      
-      execute_pipeline(config, env, pipeline)?;
-      
-      enplace_artifacts(config, env, true)?;
-    }
-  } else {
-    for pipeline_tag in &args.pipeline_tags {
-      if let Some(pipeline) = config.pipelines.iter().find(|p| p.title.as_str().eq(pipeline_tag)) {
-        let (build_path, new_build) = if args.current {
-          (curr_dir.clone(), false)
-        } else {
-          prepare_build_folder(config, builds, pipeline, &curr_dir, cache_dir, args)?
-        };
-        
-        let env = BuildEnvironment {
-          build_dir: &build_path,
-          cache_dir,
-          artifacts_dir: &artifacts_dir,
-          new_build,
-          silent_build: args.silent,
-          no_pipe: args.no_pipe,
-        };
-        
-        execute_pipeline(config, env, pipeline)?;
-        
-        enplace_artifacts(config, env, true)?;
-      } else {
-        panic!(
-          "There is no such Pipeline `{}` set up for this project. Maybe, you've forgotten set up this Pipeline for project via `{}`?",
-          pipeline_tag.green(),
-          "deployer with {pipeline-short-name-and-ver}".green(),
-        );
-      }
-    }
-  }
+      ActionRun { title: action.title.to_owned(), status }
+    })
+    .collect();
+  b_stats.last_runs.insert(pipeline.title.to_owned(), runs);
   
-  Ok(())
+  true
+}
+
+/// Определяет код завершения процесса по итогам выполнения Пайплайнов.
+pub(crate) fn outcomes_exit_code(outcomes: &[PipelineOutcome]) -> i32 {
+  if outcomes.iter().any(|o| o.aborted()) { EXIT_USER_ABORT }
+  else if outcomes.iter().any(|o| !o.success()) { EXIT_ACTION_FAILURE }
+  else { 0 }
 }
 
 pub(crate) fn clean_builds(
@@ -1,5 +1,9 @@
 use clap::{Args, Subcommand, Parser};
 use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::rw::LogFormat;
+use crate::utils::{parse_age, parse_size};
 
 /// Build and deploy your services as fast as you can.
 #[derive(Parser, Debug)]
@@ -20,6 +24,9 @@ pub(crate) struct Cli {
   /// Specify data folder
   #[arg(long)]
   pub(crate) data_folder: Option<String>,
+  /// Specify key file to unlock secrets instead of passphrase
+  #[arg(long)]
+  pub(crate) secrets_key_file: Option<PathBuf>,
 }
 
 #[derive(Subcommand, Debug)]
@@ -48,6 +55,15 @@ pub(crate) enum DeployerExecType {
   Build(BuildArgs),
   /// Clean the project's builds
   Clean(CleanArgs),
+  /// Remove old builds and logs of all projects
+  Gc(GcArgs),
+  /// Show the history of Pipelines' runs
+  History(HistoryArgs),
+  /// Print the log of a Pipeline's run
+  Logs(LogsArgs),
+  /// Manage the project's encrypted secrets
+  #[command(subcommand)]
+  Secrets(SecretsType),
   
   #[cfg(feature = "tests")]
   Tests,
@@ -128,6 +144,72 @@ pub(crate) struct WithPipelineArgs {
   /// {short-name}
   #[arg(short, long)]
   pub(crate) r#as: Option<String>,
+  /// Non-interactive setup with answers from JSON file
+  #[arg(long)]
+  pub(crate) answers: Option<PathBuf>,
+  /// Non-interactive setup with default answers
+  #[arg(long)]
+  pub(crate) defaults: bool,
+}
+
+#[derive(Subcommand, Debug)]
+pub(crate) enum SecretsType {
+  /// Set the secret's value
+  Set(SecretSetArgs),
+  /// Print the secret's value
+  Get(SecretArgs),
+  /// List the project's secrets
+  Ls,
+  /// Remove the secret
+  Rm(SecretArgs),
+}
+
+#[derive(Args, Debug)]
+pub(crate) struct SecretArgs {
+  pub(crate) name: String,
+}
+
+#[derive(Args, Debug)]
+pub(crate) struct SecretSetArgs {
+  pub(crate) name: String,
+  /// Read the secret's value from stdin instead of prompting (e.g., `... | deployer secrets set NAME --stdin`)
+  #[arg(long)]
+  pub(crate) stdin: bool,
+}
+
+#[derive(Args, Debug)]
+pub(crate) struct GcArgs {
+  /// Keep only N last used builds for each project and exclusive tag
+  #[arg(long)]
+  pub(crate) keep_last: Option<usize>,
+  /// Remove builds and logs unused for specified time (e.g., `12h`, `7d`, `2w`)
+  #[arg(long, value_parser = parse_age)]
+  pub(crate) max_age: Option<Duration>,
+  /// Remove least recently used builds until their total size fits the limit (e.g., `500M`, `10G`)
+  #[arg(long, value_parser = parse_size)]
+  pub(crate) max_size: Option<u64>,
+  /// Print what would be removed without removing anything
+  #[arg(long)]
+  pub(crate) dry_run: bool,
+}
+
+#[derive(Args, Debug)]
+pub(crate) struct HistoryArgs {
+  /// Show only the runs of specified project
+  #[arg(long)]
+  pub(crate) project: Option<String>,
+  /// Show only the runs of specified Pipeline
+  #[arg(long)]
+  pub(crate) pipeline: Option<String>,
+}
+
+#[derive(Args, Debug)]
+pub(crate) struct LogsArgs {
+  /// Run's ID or `last`
+  pub(crate) run: String,
+  /// Follow the log while the Pipeline is running
+  #[arg(short, long)]
+  pub(crate) follow: bool,
 }
 
 #[derive(Args, Debug)]
@@ -166,4 +248,33 @@ pub(crate) struct BuildArgs {
   /// Don't pipe I/O channels
   #[arg(short('t'), long)]
   pub(crate) no_pipe: bool,
+  
+  /// Run up to N Pipelines with different build folders concurrently
+  #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u16).range(1..))]
+  pub(crate) jobs: u16,
+  
+  /// Resume the last failed run of the Pipeline from the failed Action
+  #[arg(long)]
+  pub(crate) resume: bool,
+  /// Run the Pipeline starting from the Action with specified title
+  #[arg(long)]
+  pub(crate) from: Option<String>,
+  /// Run only the Action with specified title (can be repeated)
+  #[arg(long)]
+  pub(crate) only: Vec<String>,
+  
+  /// Print the commands to execute without running them
+  #[arg(long)]
+  pub(crate) dry_run: bool,
+  
+  /// Build log format (overrides the `log_format` global setting)
+  #[arg(long, value_enum)]
+  pub(crate) log_format: Option<LogFormat>,
+  
+  /// Write the build report in JSON format to the specified file
+  #[arg(long)]
+  pub(crate) report: Option<PathBuf>,
+  /// Write the build report in JUnit XML format to the specified file
+  #[arg(long)]
+  pub(crate) junit: Option<PathBuf>,
 }
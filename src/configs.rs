@@ -8,9 +8,11 @@ use crate::entities::{
   info::{ActionInfo, info2str_simple},
   targets::TargetDescription,
   programming_languages::ProgrammingLanguage,
-  variables::Variable,
+  variables::{Variable, VarTraits},
 };
 use crate::hmap;
+use crate::i18n;
+use crate::rw::LogFormat;
 use crate::utils::ordered_map;
 
 /// Конфигурация проекта.
@@ -39,6 +41,95 @@ pub(crate) struct DeployerProjectOptions {
   pub(crate) inplace_artifacts_into_project_root: Vec<(String, String)>,
 }
 
+/// Ответы для неинтерактивного назначения Пайплайна проекту (`deployer with --answers`).
+#[derive(Deserialize, Serialize, Default, Debug)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct WithPipelineAnswers {
+  /// Короткое имя Пайплайна внутри проекта.
+  #[serde(default)]
+  pub(crate) short_name: Option<String>,
+  /// Запускать ли Пайплайн по умолчанию.
+  /// 
+  /// Если не указано, Пайплайн становится Пайплайном по умолчанию только при отсутствии других.
+  #[serde(default)]
+  pub(crate) default: Option<bool>,
+  /// Порядок Пайплайнов внутри проекта (по коротким именам).
+  #[serde(default)]
+  pub(crate) order: Vec<String>,
+  /// Пропускать ли Действия, несовместимые с проектом.
+  #[serde(default)]
+  pub(crate) skip_incompatible: bool,
+  /// Подстановки: `{short-name}@{version}` Действия -> плейсхолдер -> переменная или значение.
+  #[serde(default)]
+  pub(crate) replacements: HashMap<String, HashMap<String, PlaceholderAnswer>>,
+}
+
+/// Подстановка для плейсхолдера.
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(untagged)]
+pub(crate) enum PlaceholderAnswer {
+  /// Название переменной проекта.
+  Variable(String),
+  /// Значение, подставляемое как есть (`{ "literal": "..." }`).
+  Literal(LiteralAnswer),
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct LiteralAnswer {
+  pub(crate) literal: String,
+}
+
+impl WithPipelineAnswers {
+  /// Находит переменную для плейсхолдера команды Действия.
+  /// 
+  /// Если плейсхолдер не указан в ответах, ищется переменная проекта с тем же названием.
+  pub(crate) fn resolve(&self, info: &ActionInfo, placeholder: &str, variables: &[Variable]) -> Option<Variable> {
+    if let Some(answer) = self.replacements.get(&info2str_simple(info)).and_then(|r| r.get(placeholder)) {
+      return match answer {
+        PlaceholderAnswer::Variable(title) => variables.find(title),
+        PlaceholderAnswer::Literal(LiteralAnswer { literal }) => Some(Variable::new_plain(literal, literal)),
+      }
+    }
+    
+    variables
+      .find(placeholder)
+      .or_else(|| variables.find(placeholder.trim_matches(|c| matches!(c, '<' | '>' | '{' | '}'))))
+  }
+  
+  /// Проверяет, что подстановки относятся к Действиям Пайплайна и их плейсхолдерам, а указанные переменные есть в проекте.
+  /// 
+  /// Возвращает описания всех найденных ошибок.
+  pub(crate) fn check(&self, pipeline: &DescribedPipeline, variables: &[Variable]) -> Vec<String> {
+    let mut errors = vec![];
+    
+    for (action, answers) in &self.replacements {
+      let actions = pipeline.all_actions().filter(|a| info2str_simple(&a.info).eq(action)).collect::<Vec<_>>();
+      if actions.is_empty() {
+        errors.push(format!("• `{}`: {}", action, i18n::WITH_UNKNOWN_ACTION));
+        continue
+      }
+      
+      let placeholders = actions
+        .iter()
+        .flat_map(|a| a.commands())
+        .flat_map(|c| c.placeholders.iter().flatten())
+        .collect::<Vec<_>>();
+      
+      for (placeholder, answer) in answers {
+        if !placeholders.contains(&placeholder) {
+          errors.push(format!("• `{}`: `{}` - {}", action, placeholder, i18n::WITH_UNKNOWN_PLACEHOLDER));
+        } else if let PlaceholderAnswer::Variable(title) = answer && variables.find(title).is_none() {
+          errors.push(format!("• `{}`: `{}` - {}", action, placeholder, i18n::WITH_UNKNOWN_VARIABLE.replace("{}", title)));
+        }
+      }
+    }
+    
+    errors.sort();
+    errors
+  }
+}
+
 /// Глобальная конфигурация Деплойера.
 #[derive(Deserialize, Serialize, Debug)]
 pub(crate) struct DeployerGlobalConfig {
@@ -52,6 +143,9 @@ pub(crate) struct DeployerGlobalConfig {
   /// Реестр доступных пайплайнов.
   #[serde(serialize_with = "ordered_map")]
   pub(crate) pipelines_registry: HashMap<String, DescribedPipeline>,
+  /// Формат логов сборки по умолчанию.
+  #[serde(default)]
+  pub(crate) log_format: LogFormat,
   // /// Реестр доступных зависимостей.
   // #[serde(serialize_with = "ordered_map")]
   // pub(crate) dependencies_registry: HashMap<String, DescribedDependency>,
@@ -77,8 +171,14 @@ impl Default for DeployerGlobalConfig {
           show_success_output: false,
           show_bash_c: true,
           only_when_fresh: None,
+          timeout: None,
+          retry: None,
         }],
-      })
+      }),
+      needs: None,
+      when: None,
+      inputs: None,
+      outputs: None,
     });
     
     let pipelines_registry = hmap!();
@@ -89,6 +189,70 @@ impl Default for DeployerGlobalConfig {
       templates: vec![],
       actions_registry,
       pipelines_registry,
+      log_format: LogFormat::default(),
     }
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  
+  fn pipeline() -> DescribedPipeline {
+    serde_json::from_value(serde_json::json!({
+      "title": "test",
+      "desc": "",
+      "info": "test@0.1.0",
+      "tags": [],
+      "actions": [{
+        "title": "upload",
+        "desc": "",
+        "info": "upload@0.1.0",
+        "tags": [],
+        "action": { "Custom": {
+          "bash_c": "scp <file> <host>:",
+          "placeholders": ["<file>", "<host>"],
+          "ignore_fails": false,
+          "show_success_output": false,
+          "show_bash_c": false,
+        } },
+      }],
+    })).unwrap()
+  }
+  
+  fn answers(replacements: serde_json::Value) -> anyhow::Result<WithPipelineAnswers> {
+    Ok(serde_json::from_value(serde_json::json!({ "replacements": replacements }))?)
+  }
+  
+  #[test]
+  fn answers_resolve() {
+    let variables = vec![Variable::new_plain("host", "example.com")];
+    let answers = answers(serde_json::json!({
+      "upload@0.1.0": { "<file>": { "literal": "target/app" }, "<host>": "host" },
+    })).unwrap();
+    let info = &pipeline().actions[0].info;
+    
+    assert!(answers.check(&pipeline(), &variables).is_empty());
+    assert_eq!(answers.resolve(info, "<file>", &variables).unwrap().title, "target/app");
+    assert_eq!(answers.resolve(info, "<host>", &variables).unwrap().title, "host");
+  }
+  
+  #[test]
+  fn answers_mismatches() {
+    let variables = vec![Variable::new_plain("host", "example.com")];
+    let mismatched = answers(serde_json::json!({
+      "upload@0.1.0": { "<file>": "target/app", "<port>": { "literal": "22" } },
+      "uplaod@0.1.0": { "<file>": { "literal": "target/app" } },
+    })).unwrap();
+    
+    // Значение без `literal` считается названием переменной, поэтому опечатки не подставляются как есть
+    let errors = mismatched.check(&pipeline(), &variables);
+    assert_eq!(errors.len(), 3);
+    assert!(errors[0].starts_with("• `uplaod@0.1.0`"));
+    assert!(errors[1].contains("`<file>`") && errors[1].contains("`target/app`"));
+    assert!(errors[2].contains("`<port>`"));
+    
+    assert!(answers(serde_json::json!({ "upload@0.1.0": { "<file>": { "literal": "a", "extra": 1 } } })).is_err());
+    assert!(serde_json::from_value::<WithPipelineAnswers>(serde_json::json!({ "short-name": "x" })).is_err());
+  }
+}
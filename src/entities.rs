@@ -3,6 +3,9 @@ pub(crate) mod traits;
 pub(crate) mod info;
 pub(crate) mod targets;
 pub(crate) mod custom_command;
+pub(crate) mod condition;
+pub(crate) mod retry;
+pub(crate) mod named_cache;
 pub(crate) mod variables;
 pub(crate) mod programming_languages;
 pub(crate) mod environment;
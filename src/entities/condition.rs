@@ -0,0 +1,316 @@
+use std::iter::Peekable;
+use std::str::Chars;
+
+use crate::configs::DeployerProjectOptions;
+use crate::entities::environment::BuildEnvironment;
+
+/// Условие выполнения Действия (`when`).
+/// 
+/// Поддерживаемые выражения:
+/// - `fresh` - сборка выполняется в новой папке сборки
+/// - `exists("path")` - файл или папка существует в папке сборки
+/// - `branch == "main"` - текущая ветка git в папке проекта
+/// - `var("TITLE") == "value"` - значение переменной проекта
+/// 
+/// Выражения сравниваются операторами `==` и `!=` и объединяются операторами `!`, `&&`, `||` и скобками.
+#[derive(Debug, PartialEq)]
+pub(crate) enum Condition {
+  Fresh,
+  Exists(String),
+  Eq(Operand, Operand),
+  Ne(Operand, Operand),
+  Not(Box<Condition>),
+  And(Box<Condition>, Box<Condition>),
+  Or(Box<Condition>, Box<Condition>),
+}
+
+/// Значение, участвующее в сравнении.
+#[derive(Debug, PartialEq)]
+pub(crate) enum Operand {
+  Branch,
+  Var(String),
+  Literal(String),
+}
+
+#[derive(Debug, PartialEq)]
+enum Token {
+  Ident(String),
+  Str(String),
+  LParen,
+  RParen,
+  Not,
+  And,
+  Or,
+  Eq,
+  Ne,
+}
+
+impl Condition {
+  pub(crate) fn parse(expr: &str) -> anyhow::Result<Self> {
+    let tokens = tokenize(expr).map_err(|e| anyhow::anyhow!("Invalid condition `{}`: {}", expr, e))?;
+    let mut tokens = tokens.into_iter().peekable();
+    let condition = parse_or(&mut tokens).map_err(|e| anyhow::anyhow!("Invalid condition `{}`: {}", expr, e))?;
+    if let Some(token) = tokens.next() {
+      return Err(anyhow::anyhow!("Invalid condition `{}`: unexpected {:?}", expr, token))
+    }
+    Ok(condition)
+  }
+  
+  /// Вычисляет условие для текущей сборки.
+  pub(crate) fn evaluate(&self, config: &DeployerProjectOptions, env: BuildEnvironment) -> anyhow::Result<bool> {
+    Ok(match self {
+      Condition::Fresh => env.new_build,
+      Condition::Exists(path) => env.build_dir.join(path).exists(),
+      Condition::Eq(a, b) => a.evaluate(config, env)? == b.evaluate(config, env)?,
+      Condition::Ne(a, b) => a.evaluate(config, env)? != b.evaluate(config, env)?,
+      Condition::Not(c) => !c.evaluate(config, env)?,
+      Condition::And(a, b) => a.evaluate(config, env)? && b.evaluate(config, env)?,
+      Condition::Or(a, b) => a.evaluate(config, env)? || b.evaluate(config, env)?,
+    })
+  }
+}
+
+impl Operand {
+  fn evaluate(&self, config: &DeployerProjectOptions, env: BuildEnvironment) -> anyhow::Result<String> {
+    match self {
+      Operand::Literal(value) => Ok(value.to_owned()),
+      Operand::Var(title) => match config.variables.iter().find(|v| v.title.eq(title)) {
+        Some(var) => var.get_value(env),
+        None => Err(anyhow::anyhow!("There is no `{}` variable in the project!", title)),
+      },
+      // Ветка определяется по папке проекта: папка-worktree всегда переключена на коммит, а не на ветку
+      Operand::Branch => {
+        let output = std::process::Command::new("git")
+          .args(["rev-parse", "--abbrev-ref", "HEAD"])
+          .current_dir(env.project_dir)
+          .output()
+          .map_err(|e| anyhow::anyhow!("Can't get the current git branch due to: {}", e))?;
+        if !output.status.success() {
+          return Err(anyhow::anyhow!("Can't get the current git branch: {}", String::from_utf8_lossy(&output.stderr).trim()))
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_owned())
+      },
+    }
+  }
+}
+
+fn tokenize(expr: &str) -> anyhow::Result<Vec<Token>> {
+  let mut tokens = vec![];
+  let mut chars = expr.chars().peekable();
+  
+  while let Some(c) = chars.next() {
+    match c {
+      c if c.is_whitespace() => {},
+      '(' => tokens.push(Token::LParen),
+      ')' => tokens.push(Token::RParen),
+      '!' if chars.next_if_eq(&'=').is_some() => tokens.push(Token::Ne),
+      '!' => tokens.push(Token::Not),
+      '=' if chars.next_if_eq(&'=').is_some() => tokens.push(Token::Eq),
+      '&' if chars.next_if_eq(&'&').is_some() => tokens.push(Token::And),
+      '|' if chars.next_if_eq(&'|').is_some() => tokens.push(Token::Or),
+      '"' | '\'' => tokens.push(Token::Str(read_string(&mut chars, c)?)),
+      c if c.is_alphanumeric() || c == '_' => {
+        let mut ident = c.to_string();
+        while let Some(c) = chars.next_if(|c| c.is_alphanumeric() || *c == '_') { ident.push(c); }
+        tokens.push(Token::Ident(ident));
+      },
+      c => return Err(anyhow::anyhow!("unexpected `{}`", c)),
+    }
+  }
+  
+  Ok(tokens)
+}
+
+fn read_string(chars: &mut Peekable<Chars>, quote: char) -> anyhow::Result<String> {
+  let mut s = String::new();
+  while let Some(c) = chars.next() {
+    match c {
+      '\\' => s.push(chars.next().ok_or_else(|| anyhow::anyhow!("unterminated string"))?),
+      c if c == quote => return Ok(s),
+      c => s.push(c),
+    }
+  }
+  Err(anyhow::anyhow!("unterminated string"))
+}
+
+type Tokens = Peekable<std::vec::IntoIter<Token>>;
+
+fn parse_or(tokens: &mut Tokens) -> anyhow::Result<Condition> {
+  let mut left = parse_and(tokens)?;
+  while tokens.next_if_eq(&Token::Or).is_some() {
+    left = Condition::Or(Box::new(left), Box::new(parse_and(tokens)?));
+  }
+  Ok(left)
+}
+
+fn parse_and(tokens: &mut Tokens) -> anyhow::Result<Condition> {
+  let mut left = parse_unary(tokens)?;
+  while tokens.next_if_eq(&Token::And).is_some() {
+    left = Condition::And(Box::new(left), Box::new(parse_unary(tokens)?));
+  }
+  Ok(left)
+}
+
+fn parse_unary(tokens: &mut Tokens) -> anyhow::Result<Condition> {
+  if tokens.next_if_eq(&Token::Not).is_some() { return Ok(Condition::Not(Box::new(parse_unary(tokens)?))) }
+  
+  if tokens.next_if_eq(&Token::LParen).is_some() {
+    let condition = parse_or(tokens)?;
+    expect(tokens, Token::RParen)?;
+    return Ok(condition)
+  }
+  
+  match tokens.peek() {
+    Some(Token::Ident(ident)) if ident == "fresh" => {
+      tokens.next();
+      Ok(Condition::Fresh)
+    },
+    Some(Token::Ident(ident)) if ident == "exists" => {
+      tokens.next();
+      Ok(Condition::Exists(parse_call_arg(tokens)?))
+    },
+    _ => {
+      let left = parse_operand(tokens)?;
+      match tokens.next() {
+        Some(Token::Eq) => Ok(Condition::Eq(left, parse_operand(tokens)?)),
+        Some(Token::Ne) => Ok(Condition::Ne(left, parse_operand(tokens)?)),
+        token => Err(anyhow::anyhow!("expected `==` or `!=`, got {:?}", token)),
+      }
+    },
+  }
+}
+
+fn parse_operand(tokens: &mut Tokens) -> anyhow::Result<Operand> {
+  match tokens.next() {
+    Some(Token::Str(s)) => Ok(Operand::Literal(s)),
+    Some(Token::Ident(ident)) if ident == "branch" => Ok(Operand::Branch),
+    Some(Token::Ident(ident)) if ident == "var" => Ok(Operand::Var(parse_call_arg(tokens)?)),
+    token => Err(anyhow::anyhow!("expected a string, `branch` or `var(..)`, got {:?}", token)),
+  }
+}
+
+fn parse_call_arg(tokens: &mut Tokens) -> anyhow::Result<String> {
+  expect(tokens, Token::LParen)?;
+  let arg = match tokens.next() {
+    Some(Token::Str(s)) => s,
+    token => return Err(anyhow::anyhow!("expected a string argument, got {:?}", token)),
+  };
+  expect(tokens, Token::RParen)?;
+  Ok(arg)
+}
+
+fn expect(tokens: &mut Tokens, expected: Token) -> anyhow::Result<()> {
+  match tokens.next() {
+    Some(token) if token == expected => Ok(()),
+    token => Err(anyhow::anyhow!("expected {:?}, got {:?}", expected, token)),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  
+  fn lit(s: &str) -> Operand {
+    Operand::Literal(s.to_owned())
+  }
+  
+  fn branch_is(s: &str) -> Condition {
+    Condition::Eq(Operand::Branch, lit(s))
+  }
+  
+  #[test]
+  fn tokens() {
+    assert_eq!(tokenize(r#"!(a=="x\"y")||b!='z'&&fresh"#).unwrap(), vec![
+      Token::Not,
+      Token::LParen,
+      Token::Ident("a".to_owned()),
+      Token::Eq,
+      Token::Str(r#"x"y"#.to_owned()),
+      Token::RParen,
+      Token::Or,
+      Token::Ident("b".to_owned()),
+      Token::Ne,
+      Token::Str("z".to_owned()),
+      Token::And,
+      Token::Ident("fresh".to_owned()),
+    ]);
+    
+    assert!(tokenize(r#"branch == "main"#).is_err());
+    assert!(tokenize("branch = main").is_err());
+    assert!(tokenize("a & b").is_err());
+    assert!(tokenize("a | b").is_err());
+    assert!(tokenize("a + b").is_err());
+  }
+  
+  #[test]
+  fn operands() {
+    assert_eq!(
+      Condition::parse(r#"var("ENV") != 'dev'"#).unwrap(),
+      Condition::Ne(Operand::Var("ENV".to_owned()), lit("dev")),
+    );
+    assert_eq!(
+      Condition::parse(r#"exists("target/release") && fresh"#).unwrap(),
+      Condition::And(Box::new(Condition::Exists("target/release".to_owned())), Box::new(Condition::Fresh)),
+    );
+  }
+  
+  #[test]
+  fn precedence() {
+    // `&&` связывает сильнее `||`, операторы одного приоритета группируются слева
+    assert_eq!(
+      Condition::parse(r#"branch == "a" || branch == "b" && branch == "c""#).unwrap(),
+      Condition::Or(
+        Box::new(branch_is("a")),
+        Box::new(Condition::And(Box::new(branch_is("b")), Box::new(branch_is("c")))),
+      ),
+    );
+    assert_eq!(
+      Condition::parse(r#"branch == "a" || branch == "b" || branch == "c""#).unwrap(),
+      Condition::Or(
+        Box::new(Condition::Or(Box::new(branch_is("a")), Box::new(branch_is("b")))),
+        Box::new(branch_is("c")),
+      ),
+    );
+    assert_eq!(
+      Condition::parse(r#"(branch == "a" || branch == "b") && fresh"#).unwrap(),
+      Condition::And(
+        Box::new(Condition::Or(Box::new(branch_is("a")), Box::new(branch_is("b")))),
+        Box::new(Condition::Fresh),
+      ),
+    );
+  }
+  
+  #[test]
+  fn negation() {
+    // `!` относится только к ближайшему выражению
+    assert_eq!(
+      Condition::parse(r#"!fresh && branch == "a""#).unwrap(),
+      Condition::And(Box::new(Condition::Not(Box::new(Condition::Fresh))), Box::new(branch_is("a"))),
+    );
+    assert_eq!(
+      Condition::parse(r#"!(fresh && branch == "a")"#).unwrap(),
+      Condition::Not(Box::new(Condition::And(Box::new(Condition::Fresh), Box::new(branch_is("a"))))),
+    );
+    assert_eq!(Condition::parse("!!fresh").unwrap(), Condition::Not(Box::new(Condition::Not(Box::new(Condition::Fresh)))));
+  }
+  
+  #[test]
+  fn invalid_conditions() {
+    for expr in [
+      "",
+      "branch",
+      r#""main""#,
+      r#"branch == "#,
+      r#"branch == "main" &&"#,
+      r#"(branch == "main""#,
+      r#"branch == "main")"#,
+      r#"branch == "main" fresh"#,
+      "var(ENV) == \"x\"",
+      r#"exists("a", "b")"#,
+      r#"unknown == "x""#,
+      "fresh == fresh",
+    ] {
+      assert!(Condition::parse(expr).is_err(), "`{}` must be invalid", expr);
+    }
+  }
+}
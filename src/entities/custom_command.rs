@@ -1,13 +1,20 @@
 use colored::Colorize;
 use serde::{Deserialize, Serialize};
+use std::process::{Child, ExitStatus};
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, Instant};
 
+use crate::configs::WithPipelineAnswers;
 use crate::hmap;
 use crate::i18n;
+use crate::interrupt::{interrupted, mark_interrupted, ChildGuard, TerminalGuard};
 use crate::entities::environment::BuildEnvironment;
 use crate::entities::variables::{Variable, VarTraits};
 use crate::entities::info::{ActionInfo, info2str_simple};
+use crate::entities::retry::RetryPolicy;
 use crate::entities::traits::{Edit, Execute};
-use crate::utils::tags_custom_type;
+use crate::rw::LogEvent;
+use crate::utils::{tags_custom_type, CommandTimedOut};
 
 /// Команда, исполняемая в командной строке `bash`.
 #[derive(Deserialize, Serialize, PartialEq, Clone, Debug)]
@@ -33,6 +40,15 @@ pub(crate) struct CustomCommand {
   pub(crate) show_bash_c: bool,
   /// Запускать ли действие только при новых сборках.
   pub(crate) only_when_fresh: Option<bool>,
+  /// Время ожидания завершения команды (в секундах).
+  /// 
+  /// По истечении времени группа процессов команды завершается (`SIGTERM`, затем `SIGKILL`).
+  /// Если не указано, используется время ожидания по умолчанию из Пайплайна.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub(crate) timeout: Option<u64>,
+  /// Политика повторного выполнения команды при ошибке.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub(crate) retry: Option<RetryPolicy>,
 }
 
 impl CustomCommand {
@@ -47,6 +63,8 @@ impl CustomCommand {
     let show_bash_c = inquire::Confirm::new(i18n::CMD_SHOW_BASH_C).with_default(true).prompt()?;
     let show_success_output = inquire::Confirm::new(i18n::CMD_SHOW_SUCC_OUT).with_default(false).prompt()?;
     let only_when_fresh = Some(inquire::Confirm::new(i18n::CMD_ONLY_WHEN_FRESH).with_default(false).prompt()?);
+    let timeout = specify_timeout(i18n::CMD_TIMEOUT, None)?;
+    let retry = specify_retry_policy()?;
     
     Ok(CustomCommand {
       bash_c,
@@ -55,6 +73,8 @@ impl CustomCommand {
       show_bash_c,
       show_success_output,
       only_when_fresh,
+      timeout,
+      retry,
       replacements: None,
     })
   }
@@ -72,6 +92,8 @@ impl CustomCommand {
       show_success_output: true,
       show_bash_c: false,
       only_when_fresh: Some(false),
+      timeout: None,
+      retry: None,
       replacements: None,
     })
   }
@@ -81,12 +103,14 @@ impl CustomCommand {
     info: &ActionInfo,
     variables: &[Variable],
     artifacts: &[String],
+    answers: Option<&WithPipelineAnswers>,
   ) -> anyhow::Result<Self> {
     use inquire::{Confirm, Select, Text};
     
     const USE_ANOTHER: &str = i18n::VAR_SPECIFY_ANOTHER;
     
     if self.placeholders.as_ref().is_none_or(|ps| ps.is_empty()) { return Ok(self.clone()) }
+    if let Some(answers) = answers { return self.setup_from_answers(info, variables, answers) }
     
     println!("{}", i18n::CMD_SPECIFY_VARS.replace("{}", &info2str_simple(info).blue()));
     
@@ -133,6 +157,43 @@ impl CustomCommand {
     Ok(r)
   }
   
+  /// Настраивает команду для проекта без запросов к пользователю.
+  fn setup_from_answers(
+    &self,
+    info: &ActionInfo,
+    variables: &[Variable],
+    answers: &WithPipelineAnswers,
+  ) -> anyhow::Result<Self> {
+    let mut replacement = vec![];
+    let mut r = self.clone();
+    
+    for placeholder in self.placeholders.as_ref().unwrap() {
+      let variable = answers
+        .resolve(info, placeholder, variables)
+        .ok_or_else(|| anyhow::anyhow!("{} `{}`", i18n::WITH_UNRESOLVED_PLACEHOLDER, placeholder))?;
+      if variable.is_secret { r.show_bash_c = false; }
+      replacement.push((placeholder.to_owned(), variable));
+    }
+    
+    r.replacements = Some(vec![replacement]);
+    Ok(r)
+  }
+  
+  /// Возвращает плейсхолдеры команды, для которых в ответах нет подстановок.
+  pub(crate) fn unresolved_placeholders(
+    &self,
+    info: &ActionInfo,
+    variables: &[Variable],
+    answers: &WithPipelineAnswers,
+  ) -> Vec<String> {
+    self.placeholders
+      .iter()
+      .flatten()
+      .filter(|p| answers.resolve(info, p, variables).is_none())
+      .cloned()
+      .collect()
+  }
+  
   pub(crate) fn edit_command_from_prompt(&mut self) -> anyhow::Result<()> {
     while let Some(action) = inquire::Select::new(
       &format!("{} {}:", i18n::CMD_SELECT_TO_CHANGE.replace("{}", &self.bash_c.green()), i18n::HIT_ESC),
@@ -143,6 +204,8 @@ impl CustomCommand {
         i18n::CMD_CHANGE_VISIBILITY_AT_BUILD,
         i18n::CMD_CHANGE_VISIBILITY_ON_SUCC,
         i18n::CMD_CHANGE_ON_FRESH,
+        i18n::CMD_CHANGE_TIMEOUT,
+        i18n::CMD_CHANGE_RETRY,
       ],
     ).prompt_skippable()? {
       match action {
@@ -172,6 +235,8 @@ impl CustomCommand {
             None
           };
         },
+        i18n::CMD_CHANGE_TIMEOUT => self.timeout = specify_timeout(i18n::CMD_TIMEOUT, self.timeout)?,
+        i18n::CMD_CHANGE_RETRY => self.retry = specify_retry_policy()?,
         _ => {},
       }
     }
@@ -180,6 +245,31 @@ impl CustomCommand {
   }
 }
 
+/// Возвращает оболочку для выполнения команд (`DEPLOYER_SH_PATH` или `/bin/bash`).
+pub(crate) fn get_shell() -> String {
+  match std::env::var("DEPLOYER_SH_PATH") {
+    Ok(path) => path,
+    Err(_) => "/bin/bash".to_string(),
+  }
+}
+
+/// Запрашивает время ожидания в секундах (`esc` - без ограничения).
+pub(crate) fn specify_timeout(message: &str, default: Option<u64>) -> anyhow::Result<Option<u64>> {
+  let message = format!("{} {}:", message, i18n::OR_HIT_ESC);
+  let mut prompt = inquire::CustomType::<u64>::new(&message);
+  if let Some(default) = default { prompt = prompt.with_default(default); }
+  Ok(prompt.prompt_skippable()?.filter(|secs| *secs != 0))
+}
+
+/// Запрашивает политику повторного выполнения команды.
+fn specify_retry_policy() -> anyhow::Result<Option<RetryPolicy>> {
+  if inquire::Confirm::new(i18n::CMD_RETRY_ON_FAIL).with_default(false).prompt()? {
+    Ok(Some(RetryPolicy::new_from_prompt()?))
+  } else {
+    Ok(None)
+  }
+}
+
 pub(crate) fn specify_bash_c(default: Option<&str>) -> anyhow::Result<String> {
   let mut bash_c;
   loop {
@@ -296,95 +386,507 @@ impl Edit for Vec<CustomCommand> {
   }
 }
 
-impl Execute for CustomCommand {
-  fn execute(&self, env: BuildEnvironment) -> anyhow::Result<(bool, Vec<String>)> {
+impl CustomCommand {
+  /// Описание команды для ключа кэша Действий: команда и наборы замен плейсхолдеров (по строке на каждый набор).
+  /// 
+  /// Значения секретов входят в описание лишь в виде хэша (см. `Variable::cache_key_value`).
+  pub(crate) fn cache_key_parts(&self, env: BuildEnvironment) -> anyhow::Result<Vec<String>> {
+    let mut bash_c = self.bash_c.to_owned();
+    for (from, to) in env.injected_placeholders { bash_c = bash_c.replace(from, to); }
+    let mut parts = vec![bash_c];
+    
+    let Some(replacements) = self.replacements.as_ref().filter(|_| self.placeholders.is_some()) else { return Ok(parts) };
+    for every_start in replacements {
+      let values = every_start.iter().map(|(from, to)| Ok((from, to.cache_key_value(env)?))).collect::<anyhow::Result<Vec<_>>>()?;
+      parts.push(serde_json::to_string(&values)?);
+    }
+    
+    Ok(parts)
+  }
+  
+  /// Выполняет команду.
+  /// 
+  /// Возвращает статус выполнения, сообщения для отображения после выполнения Действия
+  /// и весь перехваченный вывод команды (без секретов).
+  pub(crate) fn run(&self, env: BuildEnvironment) -> anyhow::Result<(bool, Vec<String>, Vec<String>)> {
     let mut output = vec![];
+    let mut captured = vec![];
+    
+    let mut bash_c = self.bash_c.to_owned();
+    for (from, to) in env.injected_placeholders { bash_c = bash_c.replace(from, to); }
     
     if !env.new_build && self.only_when_fresh.is_some_and(|v| v) {
-      if *crate::rw::VERBOSE.wait() {
+      if env.dry_run {
+        env.println(i18n::DRY_RUN_SKIP_NOT_FRESH.replace("{}", &bash_c.green()));
+      } else if *crate::rw::VERBOSE.wait() {
         output.push(i18n::CMD_SKIP_DUE_TO_NOT_FRESH.to_string());
       }
-      return Ok((true, output))
+      return Ok((true, output, captured))
     }
     
-    let shell = match std::env::var("DEPLOYER_SH_PATH") {
-      Ok(path) => path,
-      Err(_) => "/bin/bash".to_string(),
-    };
+    if env.dry_run {
+      self.print_plan(&bash_c, env)?;
+      return Ok((true, output, captured))
+    }
     
     if self.placeholders.is_some() && let Some(replacements) = &self.replacements {
       for every_start in replacements {
-        let mut bash_c = self.bash_c.to_owned();
-        
-        for (from, to) in every_start { bash_c = bash_c.replace(from, to.get_value()?); }
-        
-        let bash_c_info = format!(r#"{} -c "{}""#, shell, bash_c).green();
-        let mut cmd = std::process::Command::new(&shell);
-        cmd.current_dir(env.build_dir).arg("-c").arg(&bash_c);
-        
-        if !env.no_pipe { cmd.stdout(std::process::Stdio::piped()).stderr(std::process::Stdio::piped()); }
-        
-        let mut child = cmd.spawn().map_err(|e| anyhow::anyhow!("Can't execute command due to: {}", e))?;
+        let mut bash_c = bash_c.to_owned();
         
-        let success = if env.no_pipe {
-          let res = child.wait().map_err(|e| anyhow::anyhow!("Can't wait for exit status due to: {}", e))?;
-          res.success()
-        } else {
-          let command_output = child.wait_with_output().map_err(|e| anyhow::anyhow!("Can't wait for output due to: {}", e))?;
-          
-          let stdout_strs = String::from_utf8_lossy_owned(command_output.stdout);
-          let stderr_strs = String::from_utf8_lossy_owned(command_output.stderr);
-          output.extend_from_slice(&compose_output(
-            bash_c_info.to_string(),
-            stdout_strs,
-            stderr_strs,
-            command_output.status.success(),
-            self.show_success_output,
-            self.show_bash_c,
-          ));
-          
-          command_output.status.success()
-        };
+        for (from, to) in every_start {
+          let value = to.get_value(env)?;
+          if to.is_secret { env.secrets_mask.add(&value); }
+          bash_c = bash_c.replace(from, &value);
+        }
         
-        if !self.ignore_fails && !success {
-          return Ok((false, output))
+        if !self.run_with_retries(&bash_c, env, &mut captured)? && !self.ignore_fails {
+          return Ok((false, output, captured))
         }
       }
+    } else if !self.run_with_retries(&bash_c, env, &mut captured)? && !self.ignore_fails {
+      return Ok((false, output, captured))
+    }
+    
+    Ok((true, output, captured))
+  }
+  
+  /// Выводит команды, которые были бы выполнены, не запуская их (пробный запуск).
+  fn print_plan(&self, bash_c: &str, env: BuildEnvironment) -> anyhow::Result<()> {
+    let mut commands = vec![];
+    if self.placeholders.is_some() && let Some(replacements) = &self.replacements {
+      for every_start in replacements {
+        let mut bash_c = bash_c.to_owned();
+        for (from, to) in every_start { bash_c = bash_c.replace(from, &to.preview_value(env)?); }
+        commands.push(bash_c);
+      }
     } else {
-      let bash_c_info = format!(r#"{} -c "{}""#, shell, self.bash_c.as_str()).green();
-      let mut cmd = std::process::Command::new(&shell);
-      cmd.current_dir(env.build_dir).arg("-c").arg(&self.bash_c);
-      
-      if !env.no_pipe { cmd.stdout(std::process::Stdio::piped()).stderr(std::process::Stdio::piped()); }
-      
-      let mut child = cmd.spawn().map_err(|e| anyhow::anyhow!("Can't execute command due to: {}", e))?;
+      commands.push(bash_c.to_owned());
+    }
+    
+    let shell = get_shell();
+    for bash_c in commands {
+      let bash_c_info = format!(r#"{} -c "{}""#, shell, env.secrets_mask.mask(&bash_c)).green().to_string();
+      env.println(i18n::DRY_RUN_COMMAND.replace("{1}", &bash_c_info).replace("{2}", &env.build_dir.to_string_lossy()));
+    }
+    
+    Ok(())
+  }
+  
+  /// Выполняет команду, повторяя её при ошибке согласно политике повторного выполнения.
+  /// 
+  /// В перехваченный вывод попадает только вывод последней попытки.
+  fn run_with_retries(&self, bash_c: &str, env: BuildEnvironment, captured: &mut Vec<String>) -> anyhow::Result<bool> {
+    let mut attempt = 1;
+    
+    loop {
+      let (status, mut stdout, mut stderr) = self.run_bash_c(bash_c, env)?;
       
-      let success = if env.no_pipe {
-        let res = child.wait().map_err(|e| anyhow::anyhow!("Can't wait for exit status due to: {}", e))?;
-        res.success()
-      } else {
-        let command_output = child.wait_with_output().map_err(|e| anyhow::anyhow!("Can't wait for output due to: {}", e))?;
+      if
+        status != CommandStatus::Success &&
+        !interrupted() &&
+        let Some(retry) = &self.retry &&
+        retry.should_retry(attempt, &stderr)
+      {
+        let delay = retry.delay_after(attempt);
+        let message = i18n::CMD_RETRY
+          .replace("{1}", &attempt.to_string())
+          .replace("{2}", &retry.max_attempts.to_string())
+          .replace("{3}", &delay.as_secs().to_string());
+        if !env.silent_build { env.println(message.yellow()); }
+        if let Some(log) = env.log { log.message(&[message])?; }
         
-        let stdout_strs = String::from_utf8_lossy_owned(command_output.stdout);
-        let stderr_strs = String::from_utf8_lossy_owned(command_output.stderr);
-        output.extend_from_slice(&compose_output(
-          bash_c_info.to_string(),
-          stdout_strs,
-          stderr_strs,
-          command_output.status.success(),
-          self.show_success_output,
-          self.show_bash_c,
-        ));
+        let retry_at = Instant::now() + delay;
+        while Instant::now() < retry_at && !interrupted() { std::thread::sleep(Duration::from_millis(100)); }
+        if interrupted() { return Ok(false) }
         
-        command_output.status.success()
-      };
+        attempt += 1;
+        continue
+      }
+      
+      captured.append(&mut stdout);
+      captured.append(&mut stderr);
+      
+      return match status {
+        CommandStatus::Success => Ok(true),
+        CommandStatus::Failed => Ok(false),
+        CommandStatus::TimedOut(_) if self.ignore_fails => Ok(false),
+        CommandStatus::TimedOut(secs) => Err(CommandTimedOut(secs).into()),
+      }
+    }
+  }
+  
+  /// Выполняет команду в оболочке, построчно передавая её вывод в лог сборки и на экран.
+  /// 
+  /// Возвращает статус выполнения, а также строки `stdout` и `stderr`.
+  fn run_bash_c(&self, bash_c: &str, env: BuildEnvironment) -> anyhow::Result<(CommandStatus, Vec<String>, Vec<String>)> {
+    use std::os::unix::process::CommandExt;
+    use std::process::{Command, Stdio};
+    use std::sync::mpsc::RecvTimeoutError;
+    
+    let shell = get_shell();
+    let mut cmd = Command::new(&shell);
+    cmd.current_dir(env.build_dir).arg("-c").arg(bash_c);
+    
+    let timeout = self.timeout.or(env.default_timeout);
+    let deadline = timeout.map(|secs| Instant::now() + Duration::from_secs(secs));
+    
+    // В текстовом логе команда записывается вместе с её выводом
+    if let Some(log) = env.log {
+      let command = format!(r#"{} -c "{}""#, shell, env.secrets_mask.mask(bash_c));
+      log.event(LogEvent::CommandStart { command: self.show_bash_c.then_some(command.as_str()) }, &[])?;
+    }
+    
+    if env.no_pipe {
+      use std::os::unix::process::ExitStatusExt;
       
-      if !self.ignore_fails && !success {
-        return Ok((false, output))
+      // Как и при перехвате вывода, команда выполняется в отдельной группе процессов, а терминал передаётся ей
+      cmd.process_group(0);
+      let mut child = cmd.spawn().map_err(|e| anyhow::anyhow!("Can't execute command due to: {}", e))?;
+      let _guard = ChildGuard::new(child.id(), true);
+      let _terminal = TerminalGuard::new(child.id());
+      let status = wait_until(&mut child, deadline)?;
+      if let Some(status) = &status { env.exit_codes.push(status); }
+      return match status {
+        Some(res) if res.success() => Ok((CommandStatus::Success, vec![], vec![])),
+        Some(res) => {
+          if res.signal() == Some(libc::SIGINT) { mark_interrupted(); }
+          Ok((CommandStatus::Failed, vec![], vec![]))
+        },
+        None => {
+          env.exit_codes.push(&terminate(&mut child, true)?);
+          let timed_out = CommandTimedOut(timeout.unwrap_or_default());
+          if !env.silent_build { env.println(timed_out.to_string().red()); }
+          if let Some(log) = env.log { log.message(&[timed_out.to_string()])?; }
+          Ok((CommandStatus::TimedOut(timed_out.0), vec![], vec![]))
+        },
+      }
+    }
+    
+    // Команда выполняется в отдельной группе процессов, чтобы её можно было завершить вместе со всеми дочерними процессами
+    cmd.stdin(Stdio::null()).stdout(Stdio::piped()).stderr(Stdio::piped()).process_group(0);
+    let mut child = cmd.spawn().map_err(|e| anyhow::anyhow!("Can't execute command due to: {}", e))?;
+    let _guard = ChildGuard::new(child.id(), true);
+    
+    let (tx, rx) = std::sync::mpsc::channel();
+    let readers = [
+      read_lines(child.stdout.take(), OutputChannel::Stdout, tx.clone()),
+      read_lines(child.stderr.take(), OutputChannel::Stderr, tx),
+    ];
+    
+    let bash_c_info = format!(r#"{} -c "{}""#, shell, env.secrets_mask.mask(bash_c)).green().to_string();
+    let mut stream = OutputStream {
+      header: if self.show_bash_c { format!("{} `{}`:", i18n::EXECUTING, bash_c_info) } else { i18n::EXECUTING_HIDDEN.to_string() },
+      header_written: false,
+      live: self.show_success_output && !env.silent_build,
+      env,
+      stdout: MaskedLines::default(),
+      stderr: MaskedLines::default(),
+    };
+    
+    let mut timed_out = false;
+    let mut was_interrupted = false;
+    loop {
+      // Время ожидания и прерывание проверяются до получения очередной строки: непрерывно выводящая строки команда
+      // иначе не остановится
+      if deadline.is_some_and(|deadline| Instant::now() >= deadline) { timed_out = true; break }
+      if interrupted() { was_interrupted = true; break }
+      let wait_for = match deadline {
+        Some(deadline) => deadline.saturating_duration_since(Instant::now()).min(INTERRUPT_CHECK_INTERVAL),
+        None => INTERRUPT_CHECK_INTERVAL,
+      };
+      match rx.recv_timeout(wait_for) {
+        Ok((channel, line)) => stream.push(channel, line)?,
+        Err(RecvTimeoutError::Timeout) => {},
+        Err(RecvTimeoutError::Disconnected) => break,
+      }
+    }
+    
+    let status = if timed_out || was_interrupted { None } else { wait_until(&mut child, deadline)? };
+    let status = match status {
+      Some(status) => status,
+      None => {
+        let status = if was_interrupted {
+          stop_interrupted(&mut child)?
+        } else {
+          timed_out = true;
+          terminate(&mut child, true)?
+        };
+        // Вывод, оставшийся после завершения процессов
+        let drain_deadline = Instant::now() + TIMEOUT_GRACE_PERIOD;
+        while let Ok((channel, line)) = rx.recv_timeout(drain_deadline.saturating_duration_since(Instant::now())) {
+          stream.push(channel, line)?;
+        }
+        status
+      },
+    };
+    stream.finish()?;
+    env.exit_codes.push(&status);
+    
+    if !timed_out && !was_interrupted { for reader in readers { let _ = reader.join(); } }
+    
+    if !status.success() && !stream.live && !env.silent_build {
+      for line in compose_output(
+        bash_c_info,
+        stream.stdout.lines.join("\n"),
+        stream.stderr.lines.join("\n"),
+        false,
+        self.show_success_output,
+        self.show_bash_c,
+      ) {
+        env.println(line);
       }
     }
     
-    Ok((true, output))
+    let status = if timed_out {
+      let timed_out = CommandTimedOut(timeout.unwrap_or_default());
+      if !env.silent_build { env.println(timed_out.to_string().red()); }
+      if let Some(log) = env.log { log.message(&[timed_out.to_string()])?; }
+      CommandStatus::TimedOut(timed_out.0)
+    } else if status.success() {
+      CommandStatus::Success
+    } else {
+      CommandStatus::Failed
+    };
+    
+    Ok((status, stream.stdout.lines, stream.stderr.lines))
+  }
+}
+
+/// Коды завершения команд (включая повторные попытки); `None` - процесс завершён сигналом.
+#[derive(Default)]
+pub(crate) struct ExitCodes(Mutex<Vec<Option<i32>>>);
+
+impl ExitCodes {
+  fn push(&self, status: &ExitStatus) {
+    self.0.lock().unwrap_or_else(PoisonError::into_inner).push(status.code());
+  }
+  
+  pub(crate) fn take(&self) -> Vec<Option<i32>> {
+    std::mem::take(&mut *self.0.lock().unwrap_or_else(PoisonError::into_inner))
+  }
+}
+
+/// Статус выполнения команды в оболочке.
+#[derive(PartialEq)]
+enum CommandStatus {
+  Success,
+  Failed,
+  /// Команда не завершилась за указанное время (в секундах).
+  TimedOut(u64),
+}
+
+/// Время между `SIGTERM` (или `SIGINT` при прерывании) и `SIGKILL` при завершении команды.
+const TIMEOUT_GRACE_PERIOD: Duration = Duration::from_secs(10);
+
+/// Интервал проверки прерывания выполнения пользователем.
+const INTERRUPT_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Ожидает завершения процесса до указанного момента; `None` - процесс не успел завершиться.
+fn wait_until(child: &mut Child, deadline: Option<Instant>) -> anyhow::Result<Option<ExitStatus>> {
+  let Some(deadline) = deadline else {
+    return Ok(Some(child.wait().map_err(|e| anyhow::anyhow!("Can't wait for exit status due to: {}", e))?))
+  };
+  
+  loop {
+    if let Some(status) = child.try_wait().map_err(|e| anyhow::anyhow!("Can't wait for exit status due to: {}", e))? {
+      return Ok(Some(status))
+    }
+    if Instant::now() >= deadline { return Ok(None) }
+    std::thread::sleep(Duration::from_millis(50));
+  }
+}
+
+/// Завершает процесс (или всю его группу): сначала `SIGTERM`, затем, если процессы не завершились, - `SIGKILL`.
+fn terminate(child: &mut Child, group: bool) -> anyhow::Result<ExitStatus> {
+  let pid = child.id() as libc::pid_t;
+  let pid = if group { -pid } else { pid };
+  
+  // SAFETY: отправка сигнала процессу (группе процессов), порождённому Деплойером.
+  unsafe { libc::kill(pid, libc::SIGTERM); }
+  let deadline = Instant::now() + TIMEOUT_GRACE_PERIOD;
+  let status = wait_until(child, Some(deadline))?;
+  
+  // Процессы группы могут пережить завершившийся процесс команды
+  // SAFETY: сигнал `0` лишь проверяет существование процессов группы.
+  while group && Instant::now() < deadline && unsafe { libc::kill(pid, 0) } == 0 {
+    std::thread::sleep(Duration::from_millis(50));
+  }
+  
+  // SAFETY: см. выше.
+  if status.is_none() || (group && unsafe { libc::kill(pid, 0) } == 0) { unsafe { libc::kill(pid, libc::SIGKILL); } }
+  if let Some(status) = status { return Ok(status) }
+  
+  child.wait().map_err(|e| anyhow::anyhow!("Can't wait for exit status due to: {}", e))
+}
+
+/// Дожидается завершения прерванной команды (она уже получила `SIGINT`), после чего принудительно завершает
+/// оставшиеся процессы её группы - например, фоновые процессы, игнорирующие `SIGINT`.
+fn stop_interrupted(child: &mut Child) -> anyhow::Result<ExitStatus> {
+  let status = wait_until(child, Some(Instant::now() + TIMEOUT_GRACE_PERIOD))?;
+  
+  // SAFETY: отправка сигнала группе процессов, порождённой Деплойером.
+  unsafe { libc::kill(-(child.id() as libc::pid_t), libc::SIGKILL); }
+  if let Some(status) = status { return Ok(status) }
+  
+  child.wait().map_err(|e| anyhow::anyhow!("Can't wait for exit status due to: {}", e))
+}
+
+/// Выполняет команду в оболочке и возвращает её `stdout`; при неуспешном завершении возвращает ошибку с `stderr`.
+/// 
+/// Как и команды Действий, команда выполняется в отдельной группе процессов и останавливается при прерывании
+/// пользователем или по истечении времени ожидания по умолчанию.
+pub(crate) fn capture_output(bash_c: &str, env: BuildEnvironment) -> anyhow::Result<String> {
+  use std::os::unix::process::CommandExt;
+  use std::process::{Command, Stdio};
+  
+  let mut child = Command::new(get_shell())
+    .current_dir(env.build_dir)
+    .arg("-c")
+    .arg(bash_c)
+    .stdin(Stdio::null())
+    .stdout(Stdio::piped())
+    .stderr(Stdio::piped())
+    .process_group(0)
+    .spawn()
+    .map_err(|e| anyhow::anyhow!("Can't execute command due to: {}", e))?;
+  let _guard = ChildGuard::new(child.id(), true);
+  let stdout = read_all(child.stdout.take());
+  let stderr = read_all(child.stderr.take());
+  
+  let deadline = env.default_timeout.map(|secs| Instant::now() + Duration::from_secs(secs));
+  let status = loop {
+    if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+      terminate(&mut child, true)?;
+      return Err(CommandTimedOut(env.default_timeout.unwrap_or_default()).into())
+    }
+    if interrupted() {
+      stop_interrupted(&mut child)?;
+      return Err(anyhow::anyhow!(i18n::CMD_INTERRUPTED))
+    }
+    let step = Instant::now() + INTERRUPT_CHECK_INTERVAL;
+    if let Some(status) = wait_until(&mut child, Some(deadline.map_or(step, |deadline| deadline.min(step))))? { break status }
+  };
+  
+  let stdout = String::from_utf8_lossy(&stdout.join().unwrap_or_default()).trim().to_owned();
+  let stderr = String::from_utf8_lossy(&stderr.join().unwrap_or_default()).trim().to_owned();
+  if !status.success() { return Err(anyhow::anyhow!("{}", if stderr.is_empty() { status.to_string() } else { stderr })) }
+  
+  Ok(stdout)
+}
+
+/// Читает весь вывод команды в отдельном потоке, чтобы команда не блокировалась на заполненном канале.
+fn read_all(pipe: Option<impl std::io::Read + Send + 'static>) -> std::thread::JoinHandle<Vec<u8>> {
+  std::thread::spawn(move || {
+    let mut buf = vec![];
+    if let Some(mut pipe) = pipe { let _ = pipe.read_to_end(&mut buf); }
+    buf
+  })
+}
+
+impl Execute for CustomCommand {
+  fn execute(&self, env: BuildEnvironment) -> anyhow::Result<(bool, Vec<String>)> {
+    let (status, output, _) = self.run(env)?;
+    Ok((status, output))
+  }
+}
+
+#[derive(Clone, Copy)]
+enum OutputChannel {
+  Stdout,
+  Stderr,
+}
+
+/// Построчно читает вывод дочернего процесса в отдельном потоке.
+fn read_lines(
+  pipe: Option<impl std::io::Read + Send + 'static>,
+  channel: OutputChannel,
+  tx: std::sync::mpsc::Sender<(OutputChannel, String)>,
+) -> std::thread::JoinHandle<()> {
+  use std::io::BufRead;
+  
+  std::thread::spawn(move || {
+    let Some(pipe) = pipe else { return };
+    let mut reader = std::io::BufReader::new(pipe);
+    let mut buf = vec![];
+    
+    while let Ok(n) = reader.read_until(b'\n', &mut buf) && n != 0 {
+      let line = String::from_utf8_lossy(&buf);
+      let line = line.trim_end_matches(['\n', '\r']).to_owned();
+      if tx.send((channel, line)).is_err() { return }
+      buf.clear();
+    }
+  })
+}
+
+/// Строки вывода, ещё не переданные в лог и на экран, и уже переданные строки.
+/// 
+/// Строка задерживается, пока её окончание может оказаться началом секрета, разбитого на несколько строк.
+#[derive(Default)]
+struct MaskedLines {
+  pending: Vec<String>,
+  lines: Vec<String>,
+}
+
+/// Вывод выполняемой команды.
+struct OutputStream<'a> {
+  header: String,
+  header_written: bool,
+  /// Отображать ли вывод на экране по мере выполнения.
+  live: bool,
+  env: BuildEnvironment<'a>,
+  stdout: MaskedLines,
+  stderr: MaskedLines,
+}
+
+impl OutputStream<'_> {
+  fn push(&mut self, channel: OutputChannel, line: String) -> anyhow::Result<()> {
+    let mask = self.env.secrets_mask;
+    let lines = match channel {
+      OutputChannel::Stdout => &mut self.stdout,
+      OutputChannel::Stderr => &mut self.stderr,
+    };
+    
+    lines.pending.push(line);
+    let masked = mask.mask(&lines.pending.join("\n"));
+    lines.pending = masked.split('\n').map(|l| l.to_owned()).collect();
+    
+    let mut ready = vec![];
+    while !lines.pending.is_empty() && !mask.may_continue(&lines.pending) { ready.push(lines.pending.remove(0)); }
+    self.write(channel, ready)
+  }
+  
+  fn finish(&mut self) -> anyhow::Result<()> {
+    let stdout = std::mem::take(&mut self.stdout.pending);
+    let stderr = std::mem::take(&mut self.stderr.pending);
+    self.write(OutputChannel::Stdout, stdout)?;
+    self.write(OutputChannel::Stderr, stderr)
+  }
+  
+  fn write(&mut self, channel: OutputChannel, ready: Vec<String>) -> anyhow::Result<()> {
+    if ready.is_empty() { return Ok(()) }
+    
+    if !self.header_written {
+      if let Some(log) = self.env.log { log.text(std::slice::from_ref(&self.header))?; }
+      if self.live { self.env.println(&self.header); }
+      self.header_written = true;
+    }
+    
+    let formatted = ready.iter().map(|line| format!(">>> {}", line)).collect::<Vec<_>>();
+    if let Some(log) = self.env.log {
+      let stream = match channel {
+        OutputChannel::Stdout => "stdout",
+        OutputChannel::Stderr => "stderr",
+      };
+      let events = ready.iter().map(|line| LogEvent::Output { stream, line }).collect::<Vec<_>>();
+      log.events(&events, &formatted)?;
+    }
+    if self.live { formatted.iter().for_each(|line| self.env.println(line)); }
+    
+    match channel {
+      OutputChannel::Stdout => self.stdout.lines.extend(ready),
+      OutputChannel::Stderr => self.stderr.lines.extend(ready),
+    }
+    Ok(())
   }
 }
 
@@ -436,3 +938,41 @@ fn compose_output(
     output
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::tests::TestEnv;
+  
+  fn command(bash_c: &str, timeout: Option<u64>) -> CustomCommand {
+    let mut command: CustomCommand = serde_json::from_value(serde_json::json!({
+      "bash_c": bash_c,
+      "ignore_fails": false,
+      "show_success_output": false,
+      "show_bash_c": false,
+    })).unwrap();
+    command.timeout = timeout;
+    command
+  }
+  
+  #[test]
+  fn chatty_command_times_out() {
+    let env = TestEnv::new();
+    // Вывод `yes` не прерывается ни на миг, поэтому время ожидания не может проверяться лишь в паузах вывода
+    let started = Instant::now();
+    let res = command("yes", Some(1)).run(env.env());
+    
+    assert!(res.is_err_and(|e| e.is::<CommandTimedOut>()));
+    assert!(started.elapsed() < Duration::from_secs(10));
+  }
+  
+  #[test]
+  fn command_output() {
+    let env = TestEnv::new();
+    let (success, _, captured) = command("echo out; echo err >&2", Some(10)).run(env.env()).unwrap();
+    
+    assert!(success);
+    assert_eq!(captured, ["out", "err"]);
+    assert!(!command("exit 1", None).run(env.env()).unwrap().0);
+  }
+}
@@ -1,11 +1,50 @@
 use std::path::Path;
 
+use crate::entities::custom_command::ExitCodes;
+use crate::entities::variables::{SecretsMask, VarsCache};
+use crate::rw::{BuildLog, LogFormat};
+use crate::secrets::ProjectSecrets;
+
 #[derive(Clone, Copy)]
 pub(crate) struct BuildEnvironment<'a> {
   pub(crate) build_dir: &'a Path,
+  /// Папка проекта (папка с кодом, из которой запущена сборка).
+  pub(crate) project_dir: &'a Path,
   pub(crate) cache_dir: &'a Path,
   pub(crate) artifacts_dir: &'a Path,
   pub(crate) new_build: bool,
   pub(crate) silent_build: bool,
   pub(crate) no_pipe: bool,
+  /// Пробный запуск: команды не выполняются, а лишь выводятся на экран.
+  pub(crate) dry_run: bool,
+  /// Значения переменных, вычисленные в ходе выполнения Пайплайна.
+  pub(crate) vars_cache: &'a VarsCache,
+  /// Значения секретов, которые нужно скрывать в выводе.
+  pub(crate) secrets_mask: &'a SecretsMask,
+  /// Коды завершения команд, выполненных текущим Действием.
+  pub(crate) exit_codes: &'a ExitCodes,
+  /// Формат лога сборки.
+  pub(crate) log_format: LogFormat,
+  /// Лог сборки, в который построчно записывается вывод команд.
+  pub(crate) log: Option<BuildLog<'a>>,
+  /// Коммит, из которого подготовлена папка сборки (если папка сборки - `git worktree`).
+  pub(crate) commit: Option<&'a str>,
+  /// Время ожидания команд (в секундах) по умолчанию для текущего Пайплайна.
+  pub(crate) default_timeout: Option<u64>,
+  /// Секреты проекта из хранилища Деплойера.
+  pub(crate) secrets: &'a ProjectSecrets,
+  /// Префикс строк вывода в терминал (при параллельном выполнении Пайплайнов).
+  pub(crate) output_prefix: Option<&'a str>,
+  /// Плейсхолдеры, значения которых подставляются в команды Деплойером (например, в Действиях `on_failure`).
+  pub(crate) injected_placeholders: &'a [(&'static str, String)],
+}
+
+impl BuildEnvironment<'_> {
+  /// Выводит строку в терминал, добавляя префикс Пайплайна при его наличии.
+  pub(crate) fn println(&self, line: impl std::fmt::Display) {
+    match self.output_prefix {
+      Some(prefix) => println!("{} {}", prefix, line),
+      None => println!("{}", line),
+    }
+  }
 }
@@ -0,0 +1,269 @@
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use uuid::Uuid;
+
+use crate::{CACHE_DIR, NAMED_CACHES_DIR};
+use crate::entities::environment::BuildEnvironment;
+use crate::i18n;
+use crate::rw::{copy_all, remove_path, IgnoreRules};
+use crate::utils::{build_relative_path, tags_custom_type};
+
+/// Файл с ключом сохранённого кэша.
+const KEY_FILE: &str = "key";
+/// Папка с сохранёнными файлами кэша.
+const DATA_DIR: &str = "data";
+
+/// Именованный кэш Пайплайна.
+/// 
+/// Кэш хранится в папке кэша сборок отдельно для каждого проекта. Перед выполнением Пайплайна кэш восстанавливается
+/// в папку сборки, если его ключ не изменился; после успешного выполнения Пайплайна кэш с изменившимся ключом
+/// сохраняется заново.
+#[derive(Deserialize, Serialize, PartialEq, Clone, Debug)]
+pub(crate) struct NamedCache {
+  /// Название кэша (уникальное в пределах проекта).
+  pub(crate) name: String,
+  /// Кэшируемые файлы и папки (пути относительно папки сборки).
+  pub(crate) paths: Vec<String>,
+  /// Файлы, от содержимого которых зависит ключ кэша (например, `Cargo.lock`).
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub(crate) key_files: Vec<String>,
+}
+
+impl NamedCache {
+  /// Запрашивает пути и файлы ключа кэша; если пути не указаны, кэш удаляется.
+  pub(crate) fn new_from_prompt(name: &str, current: Option<&NamedCache>) -> anyhow::Result<Option<Self>> {
+    let validator = |paths: &Vec<String>| Ok(match paths.iter().try_for_each(|p| build_relative_path(p).map(|_| ())) {
+      Ok(_) => inquire::validator::Validation::Valid,
+      Err(e) => inquire::validator::Validation::Invalid(e.into()),
+    });
+    
+    let joined = current.map(|c| c.paths.join(", ")).unwrap_or_default();
+    let paths = tags_custom_type(i18n::CACHE_PATHS, if joined.is_empty() { None } else { Some(joined.as_str()) })
+      .with_validator(validator)
+      .prompt()?;
+    if paths.is_empty() { return Ok(None) }
+    
+    let joined = current.map(|c| c.key_files.join(", ")).unwrap_or_default();
+    let key_files = tags_custom_type(i18n::CACHE_KEY_FILES, if joined.is_empty() { None } else { Some(joined.as_str()) })
+      .with_validator(validator)
+      .prompt()?;
+    
+    Ok(Some(Self { name: name.to_owned(), paths, key_files }))
+  }
+  
+  /// Проверяет название, пути и файлы ключа кэша.
+  pub(crate) fn validate(&self) -> anyhow::Result<()> {
+    if self.name.is_empty() || self.name.starts_with('.') || self.name.contains(['/', '\\']) {
+      return Err(anyhow::anyhow!("Invalid cache name `{}`!", self.name))
+    }
+    for path in self.paths.iter().chain(&self.key_files) { build_relative_path(path)?; }
+    Ok(())
+  }
+  
+  /// Ключ кэша - хэш путей кэша, а также путей и содержимого файлов ключа в папке сборки.
+  fn key(&self, build_dir: &Path) -> anyhow::Result<String> {
+    let mut hasher = Sha256::new();
+    for path in self.paths.iter().chain(&self.key_files) {
+      hasher.update((path.len() as u64).to_le_bytes());
+      hasher.update(path.as_bytes());
+    }
+    for file in &self.key_files {
+      match std::fs::read(build_dir.join(file)) {
+        Ok(content) => {
+          hasher.update([1]);
+          hasher.update((content.len() as u64).to_le_bytes());
+          hasher.update(content);
+        },
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => hasher.update([0]),
+        Err(e) => return Err(anyhow::anyhow!("Can't read the `{}` cache key file due to: {}", file, e)),
+      }
+    }
+    Ok(format!("{:x}", hasher.finalize())[..16].to_owned())
+  }
+  
+  fn folder(&self, cache_dir: &Path, project_name: &str) -> PathBuf {
+    cache_dir.join(CACHE_DIR).join(NAMED_CACHES_DIR).join(project_name).join(&self.name)
+  }
+  
+  /// Восстанавливает сохранённый кэш в папку сборки; возвращает `false`, если кэша с указанным ключом нет.
+  /// 
+  /// Пути, которые уже есть в папке сборки, не перезаписываются. Если восстановить кэш не удалось (например, его
+  /// одновременно заменил другой Пайплайн), частично восстановленные пути удаляются.
+  fn restore(&self, env: BuildEnvironment, project_name: &str, key: &str) -> anyhow::Result<bool> {
+    let folder = self.folder(env.cache_dir, project_name);
+    if std::fs::read_to_string(folder.join(KEY_FILE)).ok().as_deref() != Some(key) { return Ok(false) }
+    
+    let mut restored = vec![];
+    for path in &self.paths {
+      let (cached, target) = (folder.join(DATA_DIR).join(path), env.build_dir.join(path));
+      if !cached.exists() || std::fs::symlink_metadata(&target).is_ok() { continue }
+      restored.push(target.to_owned());
+      if let Err(e) = copy_all(cached, target, &mut IgnoreRules::new(&[], &[])) {
+        for target in &restored { remove_path(target)?; }
+        return Err(e)
+      }
+    }
+    // По времени изменения папки кэша `gc --max-age` определяет, когда он использовался в последний раз;
+    // кэш уже восстановлен, поэтому ошибка (например, кэш тем временем заменён) не важна
+    let _ = std::fs::File::open(&folder).and_then(|f| f.set_modified(SystemTime::now()));
+    
+    Ok(true)
+  }
+  
+  /// Сохраняет кэш из папки сборки с указанным ключом, заменяя кэш с прежним ключом.
+  fn save(&self, env: BuildEnvironment, project_name: &str, key: &str) -> anyhow::Result<()> {
+    let folder = self.folder(env.cache_dir, project_name);
+    
+    // Кэш собирается во временной папке, чтобы прерванное сохранение не повредило сохранённый ранее кэш
+    let tmp = folder.with_extension(format!("tmp-{}", Uuid::new_v4().simple()));
+    std::fs::create_dir_all(tmp.join(DATA_DIR))?;
+    for path in &self.paths {
+      let source = env.build_dir.join(path);
+      if source.exists() { copy_all(source, tmp.join(DATA_DIR).join(path), &mut IgnoreRules::new(&[], &[]))?; }
+    }
+    std::fs::write(tmp.join(KEY_FILE), key)?;
+    
+    // Прежний кэш сначала переименовывается, чтобы не удалять папку, из которой его может восстанавливать
+    // другой Пайплайн, и чтобы параллельно выполняемые Пайплайны не удаляли только что сохранённые друг другом кэши
+    let previous = folder.with_extension(format!("old-{}", Uuid::new_v4().simple()));
+    match std::fs::rename(&folder, &previous) {
+      Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+        remove_path(&tmp)?;
+        return Err(anyhow::anyhow!("Can't save the `{}` cache due to: {}", self.name, e))
+      },
+      _ => {},
+    }
+    let res = std::fs::rename(&tmp, &folder);
+    remove_path(&previous)?;
+    if let Err(e) = res {
+      remove_path(&tmp)?;
+      // Кэш с тем же ключом мог одновременно сохранить другой Пайплайн
+      if std::fs::read_to_string(folder.join(KEY_FILE)).ok().as_deref() == Some(key) { return Ok(()) }
+      return Err(anyhow::anyhow!("Can't save the `{}` cache due to: {}", self.name, e))
+    }
+    
+    Ok(())
+  }
+}
+
+/// Восстанавливает именованные кэши Пайплайна в папку сборки.
+/// 
+/// Возвращает ключи кэшей, которые нужно сохранить после успешного выполнения Пайплайна (`None` - кэш не изменился).
+/// Ключи вычисляются до выполнения Действий, поскольку Действия могут изменять файлы ключа. Кэш, который не удалось
+/// восстановить, не считается ошибкой сборки - он будет сохранён заново.
+pub(crate) fn restore_caches(
+  caches: &[NamedCache],
+  project_name: &str,
+  env: BuildEnvironment,
+) -> anyhow::Result<Vec<Option<String>>> {
+  let mut to_save = vec![];
+  
+  for cache in caches {
+    let key = cache.key(env.build_dir)?;
+    match cache.restore(env, project_name, &key) {
+      Ok(true) => {
+        report(env, i18n::CACHE_RESTORED.replace("{1}", &cache.name).replace("{2}", &key))?;
+        to_save.push(None);
+      },
+      Ok(false) => {
+        report(env, i18n::CACHE_NOT_FOUND.replace("{1}", &cache.name).replace("{2}", &key))?;
+        to_save.push(Some(key));
+      },
+      Err(e) => {
+        warn(env, i18n::CACHE_RESTORE_FAILED.replace("{1}", &cache.name).replace("{2}", &format!("{:#}", e)))?;
+        to_save.push(Some(key));
+      },
+    }
+  }
+  
+  Ok(to_save)
+}
+
+/// Сохраняет именованные кэши Пайплайна, ключи которых изменились.
+/// 
+/// Кэш, который не удалось сохранить, не считается ошибкой сборки - Пайплайн уже выполнен успешно.
+pub(crate) fn save_caches(
+  caches: &[NamedCache],
+  project_name: &str,
+  env: BuildEnvironment,
+  keys: &[Option<String>],
+) -> anyhow::Result<()> {
+  for (cache, key) in caches.iter().zip(keys) {
+    let Some(key) = key else { continue };
+    match cache.save(env, project_name, key) {
+      Ok(_) => report(env, i18n::CACHE_SAVED.replace("{1}", &cache.name).replace("{2}", key))?,
+      Err(e) => warn(env, i18n::CACHE_SAVE_FAILED.replace("{1}", &cache.name).replace("{2}", &format!("{:#}", e)))?,
+    }
+  }
+  
+  Ok(())
+}
+
+fn report(env: BuildEnvironment, message: String) -> anyhow::Result<()> {
+  if !env.silent_build { env.println(&message); }
+  if let Some(log) = env.log { log.message(&[message])?; }
+  Ok(())
+}
+
+fn warn(env: BuildEnvironment, message: String) -> anyhow::Result<()> {
+  if !env.silent_build { env.println(message.yellow()); }
+  if let Some(log) = env.log { log.message(&[message])?; }
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::tests::TempDir;
+  
+  fn cache(paths: &[&str], key_files: &[&str]) -> NamedCache {
+    NamedCache {
+      name: "deps".to_owned(),
+      paths: paths.iter().map(|p| p.to_string()).collect(),
+      key_files: key_files.iter().map(|p| p.to_string()).collect(),
+    }
+  }
+  
+  #[test]
+  fn keys() {
+    let dir = TempDir::new();
+    let deps = cache(&["target"], &["Cargo.lock"]);
+    
+    let missing = deps.key(dir.path()).unwrap();
+    dir.write("Cargo.lock", "");
+    let empty = deps.key(dir.path()).unwrap();
+    let empty_again = deps.key(dir.path()).unwrap();
+    dir.write("Cargo.lock", "version = 3");
+    let changed = deps.key(dir.path()).unwrap();
+    // Содержимое файлов, не входящих в ключ, на ключ не влияет
+    dir.write("Cargo.toml", "[package]");
+    let unrelated = deps.key(dir.path()).unwrap();
+    let other_paths = cache(&["target", "vendor"], &["Cargo.lock"]).key(dir.path()).unwrap();
+    // Границы путей учитываются: перенос части пути между путями кэша меняет ключ
+    let split = cache(&["ab", "c"], &[]).key(dir.path()).unwrap();
+    let split_other = cache(&["a", "bc"], &[]).key(dir.path()).unwrap();
+    
+    assert_eq!(missing.len(), 16);
+    assert_eq!(empty, empty_again);
+    assert_ne!(missing, empty);
+    assert_ne!(empty, changed);
+    assert_eq!(changed, unrelated);
+    assert_ne!(changed, other_paths);
+    assert_ne!(split, split_other);
+  }
+  
+  #[test]
+  fn validation() {
+    assert!(cache(&["target", "node_modules"], &["Cargo.lock"]).validate().is_ok());
+    assert!(cache(&["../target"], &[]).validate().is_err());
+    assert!(cache(&["target"], &["/etc/passwd"]).validate().is_err());
+    
+    for name in ["", ".deps", "a/b", "a\\b"] {
+      let cache = NamedCache { name: name.to_owned(), ..cache(&["target"], &[]) };
+      assert!(cache.validate().is_err(), "{}", name);
+    }
+  }
+}
@@ -0,0 +1,79 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use crate::actions::check::specify_regex;
+use crate::i18n;
+use crate::utils::{regexopt2str, str2regexopt};
+
+/// Политика повторного выполнения команды при ошибке.
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub(crate) struct RetryPolicy {
+  /// Максимальное число попыток (включая первую).
+  pub(crate) max_attempts: u32,
+  /// Задержка перед повторной попыткой (в секундах).
+  pub(crate) delay: u64,
+  /// Способ увеличения задержки между попытками.
+  #[serde(default)]
+  pub(crate) backoff: Backoff,
+  /// Повторять команду только тогда, когда её вывод в `stderr` соответствует регулярному выражению.
+  #[serde(
+    default,
+    skip_serializing_if = "Option::is_none",
+    serialize_with = "regexopt2str",
+    deserialize_with = "str2regexopt",
+  )]
+  pub(crate) when_stderr_matches: Option<Regex>,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Clone, Copy, Debug, Default)]
+pub(crate) enum Backoff {
+  /// Одинаковая задержка перед каждой попыткой.
+  #[default]
+  Fixed,
+  /// Задержка удваивается после каждой попытки.
+  Exponential,
+}
+
+impl PartialEq for RetryPolicy {
+  fn eq(&self, other: &Self) -> bool {
+    self.max_attempts == other.max_attempts &&
+    self.delay == other.delay &&
+    self.backoff == other.backoff &&
+    self.when_stderr_matches.as_ref().map(|re| re.as_str()) == other.when_stderr_matches.as_ref().map(|re| re.as_str())
+  }
+}
+
+impl RetryPolicy {
+  pub(crate) fn new_from_prompt() -> anyhow::Result<Self> {
+    use inquire::{Confirm, CustomType, Select};
+    
+    let max_attempts = CustomType::<u32>::new(i18n::RETRY_MAX_ATTEMPTS).with_default(3).prompt()?;
+    let delay = CustomType::<u64>::new(i18n::RETRY_DELAY).with_default(5).prompt()?;
+    let backoff = match Select::new(i18n::RETRY_BACKOFF, vec![i18n::RETRY_BACKOFF_FIXED, i18n::RETRY_BACKOFF_EXP]).prompt()? {
+      i18n::RETRY_BACKOFF_EXP => Backoff::Exponential,
+      _ => Backoff::Fixed,
+    };
+    let when_stderr_matches = if Confirm::new(i18n::RETRY_ONLY_WHEN_MATCHES).with_default(false).prompt()? {
+      Some(specify_regex(i18n::RETRY_FOR_STDERR)?)
+    } else {
+      None
+    };
+    
+    Ok(Self { max_attempts, delay, backoff, when_stderr_matches })
+  }
+  
+  /// Проверяет, нужно ли повторить команду после указанной (начиная с `1`) неудачной попытки.
+  pub(crate) fn should_retry(&self, attempt: u32, stderr: &[String]) -> bool {
+    attempt < self.max_attempts &&
+    self.when_stderr_matches.as_ref().is_none_or(|re| re.is_match(&stderr.join("\n")))
+  }
+  
+  /// Задержка перед следующей попыткой после указанной (начиная с `1`) неудачной попытки.
+  pub(crate) fn delay_after(&self, attempt: u32) -> Duration {
+    match self.backoff {
+      Backoff::Fixed => Duration::from_secs(self.delay),
+      Backoff::Exponential => Duration::from_secs(self.delay.saturating_mul(2u64.saturating_pow(attempt.saturating_sub(1)))),
+    }
+  }
+}
@@ -1,6 +1,13 @@
 use colored::Colorize;
+use regex::Regex;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Mutex, PoisonError};
 
+use crate::entities::custom_command::{capture_output, specify_bash_c};
+use crate::entities::environment::BuildEnvironment;
 use crate::entities::traits::Edit;
 use crate::hmap;
 use crate::i18n;
@@ -18,13 +25,13 @@ impl Variable {
     println!("{}: {}", i18n::NOTE.green().italic(), i18n::VAR_NOTE);
     let is_secret = inquire::Confirm::new(i18n::VAR_IS_SECRET).with_default(false).prompt()?;
     
-    // TBD
-    let plain = inquire::Text::new(i18n::VAR_CONTENT).prompt()?;
+    let value = VarValue::new_from_prompt()?;
+    let is_secret = is_secret || matches!(value, VarValue::Secret(_));
     
     Ok(Variable {
       title,
       is_secret,
-      value: VarValue::Plain(plain),
+      value,
     })
   }
   
@@ -36,10 +43,53 @@ impl Variable {
     }
   }
   
-  pub(crate) fn get_value(&self) -> anyhow::Result<&str> {
+  /// Получает значение переменной.
+  /// 
+  /// Значения из переменных окружения, `.env`-файлов, команд и хранилища секретов получаются в момент выполнения команды.
+  pub(crate) fn get_value(&self, env: BuildEnvironment) -> anyhow::Result<String> {
     match &self.value {
-      VarValue::Plain(val) => Ok(val.as_str()),
-      // VarValue::FromEnvFile(_) => unimplemented!(),
+      VarValue::Plain(val) => Ok(val.to_owned()),
+      VarValue::FromCmd(bash_c) => env.vars_cache.get_or_execute(bash_c, env).map_err(|e| anyhow::anyhow!(
+        "{} ({})",
+        i18n::VAR_CMD_FAILED.replace("{1}", bash_c).replace("{2}", &self.title),
+        e,
+      )),
+      VarValue::FromEnv(name) => std::env::var(name).map_err(|_| anyhow::anyhow!(
+        "{}",
+        i18n::VAR_NO_ENV.replace("{1}", name).replace("{2}", &self.title),
+      )),
+      VarValue::FromEnvFile(env_file) => env_file.get_value(env.project_dir).map_err(|e| anyhow::anyhow!(
+        "{} ({})",
+        i18n::VAR_NO_ENV_FILE_KEY
+          .replace("{1}", &env_file.key)
+          .replace("{2}", &env_file.env_file_path)
+          .replace("{3}", &self.title),
+        e,
+      )),
+      VarValue::Secret(name) => env.secrets.get(name).map_err(|e| anyhow::anyhow!(
+        "{} ({})",
+        i18n::VAR_NO_SECRET.replace("{1}", name).replace("{2}", &self.title),
+        e,
+      )),
+    }
+  }
+  
+  /// Значение переменной для ключа кэша Действий.
+  /// 
+  /// Значения секретов и переменных, отмеченных как секретные, заменяются хэшем, чтобы не попасть в ключ в открытом виде.
+  pub(crate) fn cache_key_value(&self, env: BuildEnvironment) -> anyhow::Result<String> {
+    let value = self.get_value(env)?;
+    let value = if self.is_secret || matches!(self.value, VarValue::Secret(_)) { format!("{:x}", Sha256::digest(value)) } else { value };
+    Ok(serde_json::to_string(&VarValue::Plain(value))?)
+  }
+  
+  /// Значение переменной для пробного запуска: команды не выполняются, секреты не расшифровываются.
+  pub(crate) fn preview_value(&self, env: BuildEnvironment) -> anyhow::Result<String> {
+    if self.is_secret || matches!(self.value, VarValue::Secret(_)) { return Ok(SECRET_MASK.to_owned()) }
+    
+    match &self.value {
+      VarValue::FromCmd(bash_c) => Ok(format!("$({})", bash_c)),
+      _ => self.get_value(env),
     }
   }
   
@@ -57,7 +107,7 @@ impl Variable {
       match action {
         i18n::EDIT_TITLE => self.title = inquire::Text::new(i18n::VAR_TITLE).prompt()?,
         i18n::EDIT_VAR_SECRET => self.is_secret = inquire::Confirm::new(i18n::VAR_IS_SECRET).with_default(false).prompt()?,
-        i18n::EDIT_VALUE => self.value = VarValue::Plain(inquire::Text::new(i18n::VAR_CONTENT).prompt()?),
+        i18n::EDIT_VALUE => self.value = VarValue::new_from_prompt()?,
         _ => {},
       }
     }
@@ -68,17 +118,172 @@ impl Variable {
 
 #[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
 pub(crate) enum VarValue {
+  /// Значение, хранящееся в конфигурации.
   Plain(String),
-  // FromEnvFile(FromEnvFile),
-  // TBD
+  /// Значение из `.env`-файла.
+  FromEnvFile(FromEnvFile),
+  /// Значение из переменной окружения с указанным именем.
+  FromEnv(String),
+  /// Вывод команды (без пробельных символов по краям), выполненной в папке сборки.
+  FromCmd(String),
+  /// Секрет проекта с указанным названием из зашифрованного хранилища Деплойера.
+  Secret(String),
+}
+
+impl VarValue {
+  pub(crate) fn new_from_prompt() -> anyhow::Result<Self> {
+    use inquire::{Select, Text};
+    
+    let value_type = Select::new(
+      i18n::VAR_VALUE_TYPE,
+      vec![
+        i18n::VAR_VALUE_PLAIN,
+        i18n::VAR_VALUE_FROM_ENV_FILE,
+        i18n::VAR_VALUE_FROM_ENV,
+        i18n::VAR_VALUE_FROM_CMD,
+        i18n::VAR_VALUE_SECRET,
+      ],
+    ).prompt()?;
+    
+    let value = match value_type {
+      i18n::VAR_VALUE_FROM_ENV_FILE => {
+        let env_file_path = Text::new(i18n::VAR_ENV_FILE_PATH).with_initial_value(".env").prompt()?;
+        let key = Text::new(i18n::VAR_ENV_FILE_KEY).prompt()?;
+        VarValue::FromEnvFile(FromEnvFile { env_file_path, key })
+      },
+      i18n::VAR_VALUE_FROM_ENV => VarValue::FromEnv(Text::new(i18n::VAR_ENV_NAME).prompt()?),
+      i18n::VAR_VALUE_FROM_CMD => VarValue::FromCmd(specify_bash_c(None)?),
+      i18n::VAR_VALUE_SECRET => {
+        let name = Text::new(i18n::VAR_SECRET_NAME).prompt()?;
+        println!("{}: {}", i18n::NOTE.green().italic(), i18n::VAR_SECRET_NOTE.replace("{}", &name));
+        VarValue::Secret(name)
+      },
+      _ => VarValue::Plain(Text::new(i18n::VAR_CONTENT).prompt()?),
+    };
+    
+    Ok(value)
+  }
 }
 
-#[derive(Deserialize, Serialize, Debug, Clone)]
+/// Ссылка на значение из `.env`-файла.
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
 pub(crate) struct FromEnvFile {
+  /// Путь к `.env`-файлу (относительные пути отсчитываются от папки проекта).
   pub(crate) env_file_path: String,
   pub(crate) key: String,
 }
 
+impl FromEnvFile {
+  fn get_value(&self, project_dir: &Path) -> anyhow::Result<String> {
+    // Относительный путь не зависит от того, из какой папки запущен Деплойер и где выполняется сборка
+    let content = std::fs::read_to_string(project_dir.join(&self.env_file_path))?;
+    self.find_value(&content)
+  }
+  
+  /// Ищет значение ключа в содержимом `.env`-файла.
+  fn find_value(&self, content: &str) -> anyhow::Result<String> {
+    for line in content.lines() {
+      let line = line.trim();
+      if line.is_empty() || line.starts_with('#') { continue }
+      
+      let line = line.strip_prefix("export ").unwrap_or(line);
+      if let Some((key, value)) = line.split_once('=') && key.trim() == self.key {
+        let value = value.trim();
+        let value = value
+          .strip_prefix('"').and_then(|v| v.strip_suffix('"'))
+          .or_else(|| value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')))
+          .unwrap_or(value);
+        return Ok(value.to_owned())
+      }
+    }
+    
+    Err(anyhow::anyhow!(i18n::VAR_KEY_NOT_FOUND))
+  }
+}
+
+/// Кэш значений переменных, полученных из вывода команд, в рамках одного запуска Пайплайна.
+#[derive(Default)]
+pub(crate) struct VarsCache(Mutex<HashMap<String, String>>);
+
+impl VarsCache {
+  /// Возвращает вывод команды, выполняя её только при первом обращении.
+  /// 
+  /// Команда выполняется без блокировки кэша, чтобы параллельно выполняемые Действия не ждали медленных команд;
+  /// если одну и ту же команду одновременно выполнили несколько Действий, используется первое сохранённое значение.
+  fn get_or_execute(&self, bash_c: &str, env: BuildEnvironment) -> anyhow::Result<String> {
+    let cached = self.0.lock().map_err(|_| anyhow::anyhow!("Variables' cache is poisoned!"))?.get(bash_c).cloned();
+    if let Some(value) = cached { return Ok(value) }
+    
+    let value = capture_output(bash_c, env)?;
+    let mut cache = self.0.lock().map_err(|_| anyhow::anyhow!("Variables' cache is poisoned!"))?;
+    Ok(cache.entry(bash_c.to_owned()).or_insert(value).to_owned())
+  }
+}
+
+/// Замена значений секретных переменных в выводе.
+const SECRET_MASK: &str = "***";
+/// Минимальная длина отдельной строки многострочного секрета, которая скрывается в выводе.
+const MIN_SECRET_FRAGMENT_LEN: usize = 4;
+
+/// Значения секретных переменных, подставленных в команды в рамках одного запуска Пайплайна.
+/// 
+/// Используется для сокрытия секретов в выводе команд, в выполняемых командах и в логах.
+#[derive(Default)]
+pub(crate) struct SecretsMask(Mutex<Vec<(String, Regex)>>);
+
+impl SecretsMask {
+  /// Добавляет значение секрета, а также отдельные строки многострочного секрета.
+  pub(crate) fn add(&self, value: &str) {
+    let mut fragments = vec![value.trim()];
+    if value.trim().contains('\n') {
+      fragments.extend(value.lines().map(|line| line.trim()).filter(|line| line.chars().count() >= MIN_SECRET_FRAGMENT_LEN));
+    }
+    
+    let mut patterns = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+    for fragment in fragments {
+      if fragment.is_empty() || patterns.iter().any(|(f, _)| f.as_str().eq(fragment)) { continue }
+      
+      // Значение может быть разбито на строки в выводе команды
+      let pattern = fragment
+        .chars()
+        .filter(|c| *c != '\r' && *c != '\n')
+        .map(|c| regex::escape(&c.to_string()))
+        .collect::<Vec<_>>()
+        .join(r"(?:\r?\n)?");
+      let re = Regex::new(&pattern).unwrap_or_else(|_| Regex::new(&regex::escape(fragment)).unwrap());
+      
+      patterns.push((fragment.to_owned(), re));
+    }
+    patterns.sort_by_key(|(f, _)| std::cmp::Reverse(f.len()));
+  }
+  
+  /// Скрывает все известные значения секретов в тексте.
+  pub(crate) fn mask(&self, text: &str) -> String {
+    let patterns = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+    let mut text = text.to_owned();
+    for (_, re) in patterns.iter() { text = re.replace_all(&text, SECRET_MASK).into_owned(); }
+    text
+  }
+  
+  /// Проверяет, может ли окончание первой строки вместе с последующими строками оказаться началом секрета,
+  /// продолжение которого ещё не получено.
+  pub(crate) fn may_continue(&self, lines: &[String]) -> bool {
+    let Some(first) = lines.first() else { return false };
+    let rest = lines[1..].concat();
+    
+    let patterns = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+    if patterns.is_empty() { return false }
+    
+    first.char_indices().any(|(i, _)| {
+      let tail = format!("{}{}", &first[i..], rest);
+      patterns.iter().any(|(fragment, _)| {
+        let fragment = fragment.chars().filter(|c| *c != '\r' && *c != '\n').collect::<String>();
+        fragment.len() > tail.len() && fragment.starts_with(tail.as_str())
+      })
+    })
+  }
+}
+
 pub(crate) trait VarTraits {
   fn is_secret(&self, title: &str) -> bool;
   fn titles(&self) -> Vec<String>;
@@ -157,3 +362,110 @@ impl Edit for Vec<Variable> {
     Ok(())
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::tests::{TempDir, TestEnv};
+  use crate::utils::CommandTimedOut;
+  
+  fn env_file(key: &str) -> FromEnvFile {
+    FromEnvFile { env_file_path: ".env".to_owned(), key: key.to_owned() }
+  }
+  
+  #[test]
+  fn env_file_values() {
+    let content = r#"
+# comment
+PLAIN=value
+  SPACED = spaced value  
+export EXPORTED=exported
+DOUBLE="double quoted"
+SINGLE='single quoted'
+WITH_EQ=a=b
+EMPTY=
+"#;
+
+    assert_eq!(env_file("PLAIN").find_value(content).unwrap(), "value");
+    assert_eq!(env_file("SPACED").find_value(content).unwrap(), "spaced value");
+    assert_eq!(env_file("EXPORTED").find_value(content).unwrap(), "exported");
+    assert_eq!(env_file("DOUBLE").find_value(content).unwrap(), "double quoted");
+    assert_eq!(env_file("SINGLE").find_value(content).unwrap(), "single quoted");
+    assert_eq!(env_file("WITH_EQ").find_value(content).unwrap(), "a=b");
+    assert_eq!(env_file("EMPTY").find_value(content).unwrap(), "");
+  }
+  
+  #[test]
+  fn env_file_missing_keys() {
+    let content = "# MISSING=commented\nOTHER=value\n";
+    
+    assert!(env_file("MISSING").find_value(content).is_err());
+    assert!(env_file("OTHER_KEY").find_value(content).is_err());
+    assert!(env_file("KEY").find_value("").is_err());
+  }
+  
+  #[test]
+  fn secrets_are_masked() {
+    let mask = SecretsMask::default();
+    mask.add("pass");
+    mask.add("password");
+    mask.add("  spaced  ");
+    
+    // Более длинные секреты скрываются раньше коротких, чтобы от них не оставались части
+    assert_eq!(mask.mask("password: pass"), "***: ***");
+    assert_eq!(mask.mask("value is spaced"), "value is ***");
+    assert_eq!(mask.mask("nothing to hide"), "nothing to hide");
+  }
+  
+  #[test]
+  fn multiline_secrets_are_masked() {
+    let mask = SecretsMask::default();
+    mask.add("-----BEGIN KEY-----\nabcdefgh\nxy\n-----END KEY-----");
+    
+    // Отдельные строки многострочного секрета скрываются, кроме слишком коротких
+    assert_eq!(mask.mask("line abcdefgh"), "line ***");
+    assert_eq!(mask.mask("xy"), "xy");
+    // Значение, разбитое в выводе на строки, тоже скрывается
+    let mask = SecretsMask::default();
+    mask.add("secret");
+    assert_eq!(mask.mask("sec\r\nret"), "***");
+  }
+  
+  #[test]
+  fn secret_continuation() {
+    let mask = SecretsMask::default();
+    assert!(!mask.may_continue(&["sec".to_owned()]));
+    
+    mask.add("secret");
+    assert!(mask.may_continue(&["the sec".to_owned()]));
+    assert!(mask.may_continue(&["the s".to_owned(), "ec".to_owned()]));
+    assert!(!mask.may_continue(&["the secret".to_owned()]));
+    assert!(!mask.may_continue(&["nothing".to_owned()]));
+    assert!(!mask.may_continue(&[]));
+  }
+  
+  #[test]
+  fn env_file_is_resolved_against_project_dir() {
+    let project_dir = TempDir::new();
+    project_dir.write(".env", "KEY=from project\n");
+    
+    assert_eq!(env_file("KEY").get_value(project_dir.path()).unwrap(), "from project");
+  }
+  
+  #[test]
+  fn command_values() {
+    let env = TestEnv::new();
+    let var = |bash_c: &str| Variable { title: "cmd".to_owned(), is_secret: false, value: VarValue::FromCmd(bash_c.to_owned()) };
+    
+    assert_eq!(var("echo ' value '").get_value(env.env()).unwrap(), "value");
+    let err = var("echo oops >&2; exit 1").get_value(env.env()).unwrap_err().to_string();
+    assert!(err.contains("`cmd`") && err.contains("echo oops") && err.contains("(oops)"));
+    
+    // Время ожидания по умолчанию распространяется и на команды переменных
+    let started = std::time::Instant::now();
+    let env = BuildEnvironment { default_timeout: Some(1), ..env.env() };
+    let err = var("sleep 30").get_value(env).unwrap_err().to_string();
+    assert!(err.contains("`sleep 30`") && err.contains(&CommandTimedOut(1).to_string()));
+    assert!(started.elapsed() < std::time::Duration::from_secs(10));
+  }
+}
@@ -0,0 +1,185 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::{ACTION_CACHE_DIR, BUILD_HISTORY, CACHE_DIR, LOGS_DIR, NAMED_CACHES_DIR};
+use crate::build::Builds;
+use crate::cmd::GcArgs;
+use crate::history::{self, BuildHistory};
+use crate::i18n;
+use crate::rw::read;
+use crate::utils::format_size;
+
+/// Папка сборки из списка сборок.
+struct BuildFolder {
+  project: String,
+  exclusive_tag: Option<String>,
+  folder: PathBuf,
+  last_used: SystemTime,
+  /// Размер на диске; вычисляется только для ограничения общего размера (`--max-size`).
+  size: Option<u64>,
+}
+
+/// Очищает кэш сборок всех проектов по правилам хранения.
+/// 
+/// Помимо папок сборок, не подходящих под правила, удаляются папки сборок, отсутствующие в списке сборок,
+/// а также логи, записи кэша Действий и именованные кэши старше `--max-age`. Папки сборок и логи Пайплайнов, которые выполняются прямо сейчас, не удаляются.
+pub(crate) fn gc(
+  builds: &mut Builds,
+  cache_dir: &Path,
+  args: &GcArgs,
+) -> anyhow::Result<()> {
+  let now = SystemTime::now();
+  let expired = |time: SystemTime| args.max_age.is_some_and(|age| now.duration_since(time).unwrap_or_default() > age);
+  
+  let history = read::<BuildHistory>(cache_dir, BUILD_HISTORY);
+  let running = history.runs.iter().filter(|r| r.is_running()).collect::<Vec<_>>();
+  let in_use = |folder: &Path| running.iter().any(|r| same_path(&r.build_folder, folder));
+  
+  // Сначала - недавно использованные сборки
+  let mut candidates = builds.projects
+    .iter()
+    .flat_map(|p| p.builds.iter().filter(|b| b.folder.exists()).map(|b| BuildFolder {
+      project: p.name.to_owned(),
+      exclusive_tag: b.exclusive_tag.to_owned(),
+      folder: b.folder.to_owned(),
+      last_used: b.last_used
+        .map(SystemTime::from)
+        .or_else(|| std::fs::metadata(&b.folder).and_then(|m| m.modified()).ok())
+        .unwrap_or(SystemTime::UNIX_EPOCH),
+      size: args.max_size.map(|_| disk_usage(&b.folder)),
+    }))
+    .collect::<Vec<_>>();
+  candidates.sort_by_key(|b| std::cmp::Reverse(b.last_used));
+  
+  let mut to_remove = vec![false; candidates.len()];
+  let mut kept = HashMap::<(&str, Option<&str>), usize>::new();
+  for (i, b) in candidates.iter().enumerate() {
+    let kept = kept.entry((b.project.as_str(), b.exclusive_tag.as_deref())).or_default();
+    if !in_use(&b.folder) && (args.keep_last.is_some_and(|n| *kept >= n) || expired(b.last_used)) {
+      to_remove[i] = true;
+    } else {
+      *kept += 1;
+    }
+  }
+  
+  if let Some(max_size) = args.max_size {
+    let mut total = candidates.iter().zip(&to_remove).filter(|(_, r)| !**r).filter_map(|(b, _)| b.size).sum::<u64>();
+    for (i, b) in candidates.iter().enumerate().rev() {
+      if total <= max_size { break }
+      if to_remove[i] || in_use(&b.folder) { continue }
+      to_remove[i] = true;
+      total -= b.size.unwrap_or_default();
+    }
+  }
+  
+  let mut reclaimed = 0;
+  let mut removed_any = false;
+  for (b, _) in candidates.iter().zip(&to_remove).filter(|(_, r)| **r) {
+    reclaimed += remove(&b.folder, b.size.unwrap_or_else(|| disk_usage(&b.folder)), args.dry_run)?;
+    removed_any = true;
+  }
+  
+  // Папки сборок, о которых Деплойер больше не знает
+  let cache_path = cache_dir.join(CACHE_DIR);
+  if cache_path.exists() {
+    let mut orphans = vec![];
+    for entry in std::fs::read_dir(&cache_path)? {
+      let path = entry?.path();
+      let is_build = path.file_name().is_some_and(|n| n.to_string_lossy().starts_with("deploy-build-"));
+      if !is_build || !path.is_dir() || in_use(&path) { continue }
+      if builds.projects.iter().any(|p| p.builds.iter().any(|b| same_path(&b.folder, &path))) { continue }
+      orphans.push(path);
+    }
+    orphans.sort();
+    for path in orphans {
+      reclaimed += remove(&path, disk_usage(&path), args.dry_run)?;
+      removed_any = true;
+    }
+  }
+  
+  let mut removed_logs = vec![];
+  let logs_path = cache_path.join(LOGS_DIR);
+  if args.max_age.is_some() && logs_path.exists() {
+    let mut logs = vec![];
+    for entry in std::fs::read_dir(&logs_path)? {
+      let entry = entry?;
+      let metadata = entry.metadata()?;
+      let path = entry.path();
+      if !metadata.is_file() || !expired(metadata.modified()?) || running.iter().any(|r| r.log.eq(&path)) { continue }
+      logs.push((path, metadata.len()));
+    }
+    logs.sort();
+    for (path, size) in logs {
+      reclaimed += remove(&path, size, args.dry_run)?;
+      removed_logs.push(path);
+    }
+  }
+  
+  // Записи кэша Действий и именованные кэши проектов, которые не использовались дольше `--max-age`
+  let mut cache_folders = vec![cache_path.join(ACTION_CACHE_DIR)];
+  if args.max_age.is_some() && let Ok(projects) = std::fs::read_dir(cache_path.join(NAMED_CACHES_DIR)) {
+    for project in projects { cache_folders.push(project?.path()); }
+  }
+  if args.max_age.is_some() {
+    let mut entries = vec![];
+    for folder in cache_folders.iter().filter(|f| f.is_dir()) {
+      for entry in std::fs::read_dir(folder)? {
+        let entry = entry?;
+        if !expired(entry.metadata()?.modified()?) { continue }
+        entries.push(entry.path());
+      }
+    }
+    entries.sort();
+    for path in entries {
+      reclaimed += remove(&path, disk_usage(&path), args.dry_run)?;
+      removed_any = true;
+    }
+  }
+  
+  if !args.dry_run {
+    let removed = candidates.iter().zip(&to_remove).filter(|(_, r)| **r).map(|(b, _)| &b.folder).collect::<Vec<_>>();
+    for project in builds.projects.iter_mut() {
+      project.builds.retain(|b| b.folder.exists() && !removed.contains(&&b.folder));
+    }
+    builds.projects.retain(|p| !p.builds.is_empty());
+    
+    if !removed_logs.is_empty() || args.max_age.is_some() {
+      history::update(cache_dir, |history| history.runs.retain(|r| {
+        r.is_running() || (!removed_logs.contains(&r.log) && !expired(r.started.into()))
+      }));
+    }
+  }
+  
+  match (removed_any || !removed_logs.is_empty(), args.dry_run) {
+    (false, _) => println!("{}", i18n::GC_NOTHING),
+    (true, false) => println!("{}", i18n::GC_RECLAIMED.replace("{}", &format_size(reclaimed))),
+    (true, true) => println!("{}", i18n::GC_WOULD_RECLAIM.replace("{}", &format_size(reclaimed))),
+  }
+  
+  Ok(())
+}
+
+/// Удаляет файл или папку (при пробном запуске - только сообщает об удалении); возвращает освобождённое место.
+fn remove(path: &Path, size: u64, dry_run: bool) -> anyhow::Result<u64> {
+  let message = if dry_run { i18n::GC_WOULD_REMOVE } else { i18n::GC_REMOVED };
+  if !dry_run {
+    let res = if path.is_dir() { std::fs::remove_dir_all(path) } else { std::fs::remove_file(path) };
+    res.map_err(|e| anyhow::anyhow!("Can't remove `{}` due to: {}", path.display(), e))?;
+  }
+  println!("{}", message.replace("{1}", &path.to_string_lossy()).replace("{2}", &format_size(size)));
+  Ok(size)
+}
+
+/// Размер файла или папки на диске; символические ссылки не раскрываются.
+fn disk_usage(path: &Path) -> u64 {
+  let Ok(metadata) = std::fs::symlink_metadata(path) else { return 0 };
+  if !metadata.is_dir() { return metadata.len() }
+  std::fs::read_dir(path)
+    .map(|entries| entries.flatten().map(|e| disk_usage(&e.path())).sum())
+    .unwrap_or_default()
+}
+
+fn same_path(a: &Path, b: &Path) -> bool {
+  a.eq(b) || matches!((a.canonicalize(), b.canonicalize()), (Ok(a), Ok(b)) if a.eq(&b))
+}
@@ -0,0 +1,250 @@
+use chrono::{DateTime, Local};
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::BUILD_HISTORY;
+use crate::cmd::{HistoryArgs, LogsArgs};
+use crate::i18n;
+use crate::pipelines::ActionStatus;
+use crate::rw::{log, read, read_checked, write, FileLock};
+use crate::utils::format_table;
+
+/// История запусков Пайплайнов.
+#[derive(Deserialize, Serialize, Default)]
+pub(crate) struct BuildHistory {
+  pub(crate) runs: Vec<RunRecord>,
+}
+
+/// Запись о запуске Пайплайна.
+#[derive(Deserialize, Serialize, Clone)]
+pub(crate) struct RunRecord {
+  /// Идентификатор запуска.
+  pub(crate) id: String,
+  pub(crate) project: String,
+  pub(crate) pipeline: String,
+  /// Время начала выполнения.
+  pub(crate) started: DateTime<Local>,
+  /// Длительность выполнения (в секундах); отсутствует, пока Пайплайн выполняется.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub(crate) duration: Option<f64>,
+  /// Статус Пайплайна; отсутствует, пока Пайплайн выполняется.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub(crate) status: Option<ActionStatus>,
+  /// Папка сборки.
+  pub(crate) build_folder: PathBuf,
+  /// Коммит, из которого подготовлена папка сборки.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub(crate) commit: Option<String>,
+  /// Лог сборки.
+  pub(crate) log: PathBuf,
+  /// Идентификатор процесса Деплойера, выполняющего Пайплайн.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub(crate) pid: Option<u32>,
+}
+
+impl RunRecord {
+  /// Статус Пайплайна; `None` - Пайплайн выполняется.
+  /// 
+  /// Запуск без статуса, процесс которого уже завершился (например, был убит), считается прерванным.
+  pub(crate) fn actual_status(&self) -> Option<ActionStatus> {
+    self.status.or_else(|| (!self.pid.is_some_and(process_exists)).then_some(ActionStatus::Aborted))
+  }
+  
+  /// Выполняется ли Пайплайн прямо сейчас.
+  pub(crate) fn is_running(&self) -> bool {
+    self.actual_status().is_none()
+  }
+}
+
+/// Проверяет, существует ли процесс.
+fn process_exists(pid: u32) -> bool {
+  let Ok(pid) = libc::pid_t::try_from(pid) else { return false };
+  // SAFETY: сигнал `0` лишь проверяет существование процесса.
+  unsafe { libc::kill(pid, 0) == 0 || std::io::Error::last_os_error().raw_os_error() == Some(libc::EPERM) }
+}
+
+/// Файл межпроцессной блокировки истории запусков.
+const HISTORY_LOCK: &str = "deploy-history.lock";
+
+/// Изменяет историю запусков, перечитывая её перед изменением.
+/// 
+/// Историю одновременно изменяют параллельно выполняемые Пайплайны, другие запуски Деплойера и `deployer gc`,
+/// поэтому она изменяется под межпроцессной блокировкой. Повреждённая история не перезаписывается.
+pub(crate) fn update(cache_dir: &Path, f: impl FnOnce(&mut BuildHistory)) {
+  let _lock = match FileLock::exclusive(cache_dir.join(HISTORY_LOCK)) {
+    Ok(lock) => lock,
+    Err(e) => { log(format!("Can't update the build history: {}", e)); return },
+  };
+  
+  let path = cache_dir.join(BUILD_HISTORY);
+  let mut history = match read_checked::<BuildHistory>(&path) {
+    Ok(history) => history,
+    Err(_) if !path.exists() => BuildHistory::default(),
+    Err(e) => { log(format!("Can't update the build history: {}", e)); return },
+  };
+  f(&mut history);
+  write(cache_dir, BUILD_HISTORY, &history);
+}
+
+/// Генерирует идентификатор нового запуска.
+pub(crate) fn new_run_id() -> String {
+  Uuid::new_v4().simple().to_string()[..8].to_owned()
+}
+
+/// Добавляет в историю запись о начале выполнения Пайплайна.
+pub(crate) fn record_start(cache_dir: &Path, record: RunRecord) {
+  update(cache_dir, |history| history.runs.push(record));
+}
+
+/// Отмечает в истории завершение выполнения Пайплайна.
+pub(crate) fn record_finish(cache_dir: &Path, id: &str, status: ActionStatus, elapsed: Duration) {
+  update(cache_dir, |history| if let Some(run) = history.runs.iter_mut().find(|r| r.id.eq(id)) {
+    run.status = Some(status);
+    run.duration = Some(elapsed.as_secs_f64());
+  });
+}
+
+/// Выводит историю запусков Пайплайнов.
+pub(crate) fn history(
+  cache_dir: &Path,
+  args: &HistoryArgs,
+) -> anyhow::Result<()> {
+  let history = read::<BuildHistory>(cache_dir, BUILD_HISTORY);
+  let runs = history.runs
+    .iter()
+    .filter(|r| args.project.as_ref().is_none_or(|p| r.project.eq(p)))
+    .filter(|r| args.pipeline.as_ref().is_none_or(|p| r.pipeline.eq(p)))
+    .collect::<Vec<_>>();
+  
+  if runs.is_empty() {
+    println!("{}", i18n::HISTORY_EMPTY);
+    return Ok(())
+  }
+  
+  let rows = runs
+    .iter()
+    .map(|r| vec![
+      r.id.to_owned(),
+      r.project.to_owned(),
+      r.pipeline.to_owned(),
+      r.started.format("%Y-%m-%d %H:%M:%S").to_string(),
+      r.duration.map(|secs| format!("{:.2?}", Duration::from_secs_f64(secs))).unwrap_or_default(),
+      r.actual_status().map_or(i18n::RUNNING, |s| s.describe()).trim().to_owned(),
+      r.build_folder.to_string_lossy().to_string(),
+    ])
+    .collect::<Vec<_>>();
+  
+  let header = [
+    i18n::HISTORY_RUN,
+    i18n::HISTORY_PROJECT,
+    i18n::SUMMARY_PIPELINE,
+    i18n::HISTORY_STARTED,
+    i18n::SUMMARY_ELAPSED,
+    i18n::SUMMARY_STATUS,
+    i18n::HISTORY_BUILD_FOLDER,
+  ];
+  let (header, separator, lines) = format_table(&header, &rows);
+  
+  println!("{}", header.bold());
+  println!("{}", separator);
+  for (line, run) in lines.iter().zip(runs) {
+    match run.actual_status() {
+      None => println!("{}", line.yellow()),
+      Some(status) if status.is_ok() => println!("{}", line.green()),
+      Some(_) => println!("{}", line.red()),
+    }
+  }
+  
+  Ok(())
+}
+
+/// Выводит лог запуска Пайплайна; с опцией `--follow` выводит новые строки лога, пока Пайплайн выполняется.
+pub(crate) fn logs(
+  cache_dir: &Path,
+  project_name: &str,
+  args: &LogsArgs,
+) -> anyhow::Result<()> {
+  use std::io::Write;
+  
+  // `last` - последний запуск в текущем проекте (или вообще последний запуск, если команда вызвана вне проекта)
+  let find_run = || {
+    let history = read::<BuildHistory>(cache_dir, BUILD_HISTORY);
+    let mut runs = history.runs.into_iter().rev();
+    if args.run.eq("last") {
+      runs.find(|r| project_name.is_empty() || r.project.eq(project_name))
+    } else {
+      runs.find(|r| r.id.eq(&args.run))
+    }
+  };
+  
+  let Some(run) = find_run() else { return Err(anyhow::anyhow!("{}", i18n::RUN_NOT_FOUND.replace("{}", &args.run))) };
+  let mut file = std::fs::File::open(&run.log)
+    .map_err(|e| anyhow::anyhow!("Can't open the `{}` log due to: {}", run.log.display(), e))?;
+  let mut stdout = std::io::stdout();
+  std::io::copy(&mut file, &mut stdout)?;
+  
+  if !args.follow { return Ok(()) }
+  
+  loop {
+    // Статус проверяется до чтения, чтобы не потерять строки, записанные перед завершением Пайплайна
+    let finished = find_run().is_none_or(|r| !r.is_running());
+    let copied = std::io::copy(&mut file, &mut stdout)?;
+    stdout.flush()?;
+    if copied == 0 {
+      if finished { break }
+      std::thread::sleep(Duration::from_millis(250));
+    }
+  }
+  
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::rw::VERBOSE;
+  use crate::tests::TempDir;
+  
+  fn record() -> RunRecord {
+    RunRecord {
+      id: new_run_id(),
+      project: "project".to_owned(),
+      pipeline: "build".to_owned(),
+      started: Local::now(),
+      duration: None,
+      status: None,
+      build_folder: PathBuf::from("build"),
+      commit: None,
+      log: PathBuf::from("build.log"),
+      pid: None,
+    }
+  }
+  
+  #[test]
+  fn concurrent_updates() {
+    let _ = VERBOSE.set(false);
+    let dir = TempDir::new();
+    
+    std::thread::scope(|s| for _ in 0..8 {
+      s.spawn(|| for _ in 0..10 { record_start(dir.path(), record()); });
+    });
+    
+    let history = read_checked::<BuildHistory>(dir.path().join(BUILD_HISTORY)).unwrap();
+    assert_eq!(history.runs.len(), 80);
+    // Остаются лишь история и файл блокировки, без временных файлов
+    assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 2);
+  }
+  
+  #[test]
+  fn corrupted_history_is_kept() {
+    let _ = VERBOSE.set(false);
+    let dir = TempDir::new();
+    std::fs::write(dir.path().join(BUILD_HISTORY), "{\"runs\": [").unwrap();
+    
+    record_start(dir.path(), record());
+    assert_eq!(std::fs::read_to_string(dir.path().join(BUILD_HISTORY)).unwrap(), "{\"runs\": [");
+  }
+}
@@ -36,6 +36,18 @@ tr!(CMD_IGNORE_FAILS, "Ignore command failures?");
 tr!(CMD_SHOW_BASH_C, "Show an entire command at build stage?");
 tr!(CMD_SHOW_SUCC_OUT, "Show an output of command if it executed successfully?");
 tr!(CMD_ONLY_WHEN_FRESH, "Start a command only in fresh builds?");
+tr!(CMD_TIMEOUT, "Specify the command's timeout in seconds");
+tr!(CMD_TIMED_OUT, "Command is timed out after {} s and terminated!");
+tr!(CMD_INTERRUPTED, "Command is interrupted by the user!");
+tr!(CMD_RETRY_ON_FAIL, "Retry the command on failure?");
+tr!(CMD_RETRY, "Attempt {1}/{2} failed, retrying in {3} s...");
+tr!(RETRY_MAX_ATTEMPTS, "Specify the maximum number of attempts:");
+tr!(RETRY_DELAY, "Specify the delay between attempts in seconds:");
+tr!(RETRY_BACKOFF, "Select the delay's backoff:");
+tr!(RETRY_BACKOFF_FIXED, "Fixed");
+tr!(RETRY_BACKOFF_EXP, "Exponential (the delay doubles after every attempt)");
+tr!(RETRY_ONLY_WHEN_MATCHES, "Retry only when the command's stderr matches a regex?");
+tr!(RETRY_FOR_STDERR, "for the command's stderr");
 
 tr!(CUSTOM_CMD_GUIDE_TITLE, "Shell Commands for Deployer");
 tr!(CUSTOM_CMD_GUIDE_1, "The usage of shell commands in Deployer is very simple.");
@@ -63,6 +75,8 @@ tr!(CMD_CHANGE_FAILURE_IGNORANCE, "Change command failure ignorance");
 tr!(CMD_CHANGE_VISIBILITY_AT_BUILD, "Change whether command is displayed or not on build stage");
 tr!(CMD_CHANGE_VISIBILITY_ON_SUCC, "Change whether command output is displayed or not when it executed successfully");
 tr!(CMD_CHANGE_ON_FRESH, "Change command executing only at fresh builds");
+tr!(CMD_CHANGE_TIMEOUT, "Change command's timeout");
+tr!(CMD_CHANGE_RETRY, "Change command's retry policy");
 
 tr!(CMDS_REORDER, "Reorder Action's commands:");
 
@@ -122,6 +136,22 @@ tr!(NOTE, "Note");
 tr!(VAR_NOTE, "if variable is a secret, then no command containing this variable will be printed during the build stage.");
 tr!(VAR_IS_SECRET, "Is this variable a secret?");
 tr!(VAR_CONTENT, "Enter the variable's content:");
+tr!(VAR_VALUE_TYPE, "Select where to get the variable's value from:");
+tr!(VAR_VALUE_PLAIN, "Plain value");
+tr!(VAR_VALUE_FROM_ENV_FILE, "`.env` file");
+tr!(VAR_VALUE_FROM_ENV, "Environment variable");
+tr!(VAR_VALUE_FROM_CMD, "Command output");
+tr!(VAR_ENV_FILE_PATH, "Enter the path to `.env` file (relative to the project's folder):");
+tr!(VAR_ENV_FILE_KEY, "Enter the key inside `.env` file:");
+tr!(VAR_ENV_NAME, "Enter the environment variable's name:");
+tr!(VAR_NO_ENV, "There is no `{1}` environment variable for `{2}` variable!");
+tr!(VAR_NO_ENV_FILE_KEY, "Can't get `{1}` key from `{2}` file for `{3}` variable!");
+tr!(VAR_CMD_FAILED, "Can't get output of `{1}` command for `{2}` variable!");
+tr!(VAR_KEY_NOT_FOUND, "key not found");
+tr!(VAR_VALUE_SECRET, "Deployer's encrypted secret");
+tr!(VAR_SECRET_NAME, "Enter the secret's name:");
+tr!(VAR_SECRET_NOTE, "don't forget to set the secret's value via `deployer secrets set {}`.");
+tr!(VAR_NO_SECRET, "Can't get `{1}` secret for `{2}` variable!");
 
 tr!(VAR_EDIT, "Edit variable");
 tr!(VAR_SELECT_FC, "Select a concrete variable to change");
@@ -166,6 +196,14 @@ tr!(EDIT_DESC, "Edit description");
 tr!(EDIT_TAGS, "Edit tags");
 tr!(EDIT_PC_FILES, "Edit files and folders to remove");
 tr!(EDIT_EXCL_TAG, "Edit exclusive execution tag");
+tr!(EDIT_DEFAULT_TIMEOUT, "Edit default commands' timeout");
+tr!(EDIT_BUILD_SOURCE, "Edit the way to prepare build folders");
+tr!(EDIT_PIPELINE_CACHES, "Edit Pipeline's named caches");
+tr!(EDIT_ACTIONS_NEEDS, "Edit Actions' dependencies");
+tr!(EDIT_ACTIONS_CONDITIONS, "Edit Actions' conditions");
+tr!(EDIT_ACTIONS_CACHE, "Edit Actions' cache inputs and outputs");
+tr!(EDIT_ON_FAILURE_ACTIONS, "Edit Actions executed on Pipeline's failure");
+tr!(EDIT_ALWAYS_ACTIONS, "Edit Actions executed after Pipeline in any case");
 tr!(EDIT_PIPELINE_ACTIONS, "Edit Pipeline's Actions");
 tr!(EDIT_PROJECT_NAME, "Edit project name");
 tr!(EDIT_PROJECT_PIPELINES, "Edit project Pipelines");
@@ -190,6 +228,21 @@ tr!(PIPELINE_DESC, "Write the Pipeline's description:");
 tr!(PIPELINE_TAGS, "Write Pipeline's tags, if any:");
 
 tr!(PIPELINE_SPECIFY_EXCL_TAG, "Specify exclusive pipeline tag");
+tr!(PIPELINE_SPECIFY_DEFAULT_TIMEOUT, "Specify the default timeout for Pipeline's commands in seconds");
+tr!(PIPELINE_SPECIFY_BUILD_SOURCE, "Specify how to prepare build folders for the Pipeline");
+tr!(BUILD_SOURCE_COPY, "Copy project files");
+tr!(BUILD_SOURCE_WORKTREE, "Git worktree of the current commit");
+tr!(BUILD_SOURCE_WORKTREE_WITH_CHANGES, "Git worktree of the current commit with uncommitted changes");
+tr!(PIPELINE_SELECT_ACTION_FOR_NEEDS, "Select the Action to edit its dependencies");
+tr!(PIPELINE_ACTION_NEEDS, "Select the Actions that must be done before this one (none - after the previous Action)");
+tr!(PIPELINE_SELECT_ACTION_FOR_WHEN, "Select the Action to edit its condition");
+tr!(PIPELINE_ACTION_WHEN, "Specify the condition (e.g., `fresh && branch == \"main\"`)");
+tr!(CACHE_NAME, "Write the cache's name (an existing name - edit or remove the cache):");
+tr!(CACHE_PATHS, "Specify cached files and folders (e.g., `target, node_modules`); none - remove the cache");
+tr!(CACHE_KEY_FILES, "Specify files the cache's key depends on (e.g., `Cargo.lock`)");
+tr!(PIPELINE_SELECT_ACTION_FOR_CACHE, "Select the Action to edit its cache inputs and outputs");
+tr!(PIPELINE_ACTION_INPUTS, "Specify the Action's input files as globs (e.g., `src/**/*.rs, Cargo.*`); none - don't cache the Action");
+tr!(PIPELINE_ACTION_OUTPUTS, "Specify the Action's output files and folders (e.g., `target/release/app`)");
 
 tr!(PIPELINES_AVAILABLE, "Available Pipelines in Deployer's Registry:");
 tr!(NO_PIPELINES, "There is no Pipelines in Registry.");
@@ -215,6 +268,15 @@ tr!(PIPELINE_SHORT_NAME_FOR_PROJECT, "Write the Pipeline's short name (only for
 tr!(PIPELINE_NEW_DEFAULT, "Set this Pipeline running by default? (y/n)");
 tr!(PIPELINE_NEW_DEFAULT_REPLACE, "Pipeline `{}` is already set by default. Set this Pipeline running by default instead?");
 tr!(PIPELINE_DEFAULT_SET, "Pipeline is successfully set up for this project.");
+tr!(WITH_ANSWERS_INVALID, "Can't read provided answers file due to:");
+tr!(WITH_TAG_REQUIRED, "Specify the Pipeline's `{short-name}@{version}` for non-interactive setup.");
+tr!(WITH_UNRESOLVED, "There are no answers for these placeholders:");
+tr!(WITH_UNRESOLVED_PLACEHOLDER, "There is no answer for placeholder");
+tr!(WITH_ANSWERS_MISMATCH, "Answers don't match the Pipeline:");
+tr!(WITH_UNKNOWN_ACTION, "there is no such Action in the Pipeline");
+tr!(WITH_UNKNOWN_PLACEHOLDER, "the Action's commands have no such placeholder");
+tr!(WITH_UNKNOWN_VARIABLE, "there is no `{}` variable in the project (use `{ \"literal\": \"...\" }` for a plain value)");
+tr!(IN_CMD, "in command");
 
 tr!(PIPELINE_SHORT_NAME_FOR_PROJECT_OVERRIDE, "Do you want to overwrite an existing pipeline `{}` for this project? (y/n)");
 tr!(PIPELINE_EDIT, "Edit Pipeline `{1}` - `{2}`");
@@ -224,14 +286,62 @@ tr!(PIPELINE_REORDER_ACTIONS, "Reorder Pipeline's Actions:");
 tr!(PIPELINE_REMOVE, "Remove Pipeline `{1}` - `{2}`");
 
 tr!(STARTING_PIPELINE, "Starting the `{}` Pipeline...");
+tr!(RUNNING_ON_FAILURE_ACTIONS, "Running Actions on Pipeline's failure (`on_failure`)...");
+tr!(RUNNING_ALWAYS_ACTIONS, "Running final Actions (`always`)...");
 tr!(STARTING_ACTION, "Action");
 tr!(ARTIFACTS_ENPLACED, "Artifacts are enplaced successfully.");
 tr!(INTERRUPT, "The Pipeline is interrupted. Hit `Enter` to continue");
 
 tr!(BUILD_PATH, "Build path");
+tr!(BUILD_COMMIT, "Commit");
+tr!(CACHE_RESTORED, "Cache `{1}` is restored (key `{2}`)");
+tr!(CACHE_NOT_FOUND, "Cache `{1}` with key `{2}` isn't found, it'll be saved after the Pipeline succeeds");
+tr!(CACHE_SAVED, "Cache `{1}` is saved (key `{2}`)");
+tr!(CACHE_RESTORE_FAILED, "Can't restore the `{1}` cache, it'll be saved again after the Pipeline succeeds: {2}");
+tr!(CACHE_SAVE_FAILED, "Can't save the `{1}` cache: {2}");
 
 tr!(DONE, " done");
 tr!(GOT_ERROR, " got an error!");
+tr!(ABORTED, " aborted by user!");
+tr!(INTERRUPTING, "Interrupting... Press `Ctrl-C` again to kill running commands.");
+tr!(TIMED_OUT, " timed out!");
+tr!(SKIPPED, " skipped");
+tr!(CONDITION_NOT_MET, " skipped");
+tr!(PREVIOUSLY_DONE, " already done");
+tr!(NOT_SELECTED, " not selected");
+tr!(PLANNED, " planned");
+tr!(CACHED, " restored from cache");
+tr!(ACTION_CACHE_HIT, "Inputs haven't changed, the Action's outputs are restored from the cache (`{}`)");
+tr!(ACTION_CACHE_NO_OUTPUTS, "The Action hasn't created all its outputs, so it isn't cached");
+tr!(DRY_RUN_COMMAND, "Would execute {1} in `{2}`");
+tr!(DRY_RUN_SKIP_NOT_FRESH, "Would skip `{}`: the command runs only on fresh builds");
+tr!(DRY_RUN_REMOVE, "Would remove `{}`");
+tr!(DRY_RUN_COPY, "Would copy artifact `{1}` to `{2}`");
+tr!(DRY_RUN_CONDITION, "The `{}` condition is checked only during the build");
+tr!(DRY_RUN_INTERRUPT, "The build would be paused here for confirmation");
+tr!(DRY_RUN_BUILD_FOLDER, "Build folder: `{}`");
+tr!(DRY_RUN_NEW_BUILD_FOLDER, "Build folder: `{}` (fresh build)");
+tr!(DRY_RUN_DONE, "Dry run of the `{}` Pipeline is finished, nothing was executed.");
+tr!(CONDITION_IS_FALSE, "The condition `{}` isn't met.");
+
+tr!(PIPELINE_DONE, "Pipeline `{1}` is done ({2}).");
+tr!(PIPELINE_FAILED, "Pipeline `{1}` is stopped at Action `{2}` ({3}).");
+tr!(SUMMARY_PIPELINE, "Pipeline");
+tr!(SUMMARY_STATUS, "Status");
+tr!(SUMMARY_ACTION, "Stopped at");
+tr!(SUMMARY_ELAPSED, "Time");
+tr!(RUNNING, " running");
+tr!(HISTORY_RUN, "Run");
+tr!(HISTORY_PROJECT, "Project");
+tr!(HISTORY_STARTED, "Started");
+tr!(HISTORY_BUILD_FOLDER, "Build folder");
+tr!(HISTORY_EMPTY, "There are no runs yet.");
+tr!(RUN_NOT_FOUND, "There is no `{}` run in the history!");
+tr!(GC_REMOVED, "Removed `{1}` ({2})");
+tr!(GC_WOULD_REMOVE, "Would remove `{1}` ({2})");
+tr!(GC_RECLAIMED, "Reclaimed {}.");
+tr!(GC_WOULD_RECLAIM, "{} would be reclaimed.");
+tr!(GC_NOTHING, "Nothing to clean up.");
 
 // Project
 tr!(PROJECT_NAME, "Enter the project's name:");
@@ -259,3 +369,13 @@ tr!(ADD_NEW_INPLACEMENT_FIRST, "Do you want to create artifact inplacement from
 tr!(ADD_NEW_INPLACEMENT_SECOND, "Add one more artifact inplacement?");
 
 tr!(INIT_SUCC, "Setup is completed. Don't forget to assign at least one Pipeline to the project to build/deploy!");
+
+// Secrets
+tr!(SECRETS_PASSPHRASE, "Enter the passphrase for Deployer's secrets:");
+tr!(SECRETS_PASSPHRASE_CONFIRM, "Confirm the passphrase:");
+tr!(SECRETS_WRONG_PASSPHRASE, "Can't unlock Deployer's secrets: wrong passphrase or key file!");
+tr!(SECRET_VALUE, "Enter the secret's value:");
+tr!(SECRET_NOT_FOUND, "There is no `{1}` secret for `{2}` project!");
+tr!(SECRETS_EMPTY, "There are no secrets for this project.");
+tr!(SECRET_SAVED, "Secret `{}` is saved.");
+tr!(SECRET_REMOVED, "Secret `{}` is removed.");
@@ -36,6 +36,18 @@ tr!(CMD_IGNORE_FAILS, "Игнорировать ошибки выполнени
 tr!(CMD_SHOW_BASH_C, "Показывать команду на этапе сборки?");
 tr!(CMD_SHOW_SUCC_OUT, "Показывать вывод команды, если она была выполнена успешно?");
 tr!(CMD_ONLY_WHEN_FRESH, "Запускать команду только на свежих сборках?");
+tr!(CMD_TIMEOUT, "Укажите время ожидания завершения команды в секундах");
+tr!(CMD_TIMED_OUT, "Команда не завершилась за {} с и была остановлена!");
+tr!(CMD_INTERRUPTED, "Команда прервана пользователем!");
+tr!(CMD_RETRY_ON_FAIL, "Повторять команду при ошибке?");
+tr!(CMD_RETRY, "Попытка {1}/{2} завершилась с ошибкой, повтор через {3} с...");
+tr!(RETRY_MAX_ATTEMPTS, "Укажите максимальное число попыток:");
+tr!(RETRY_DELAY, "Укажите задержку между попытками в секундах:");
+tr!(RETRY_BACKOFF, "Выберите способ увеличения задержки:");
+tr!(RETRY_BACKOFF_FIXED, "Фиксированная задержка");
+tr!(RETRY_BACKOFF_EXP, "Экспоненциальная задержка (удваивается после каждой попытки)");
+tr!(RETRY_ONLY_WHEN_MATCHES, "Повторять только тогда, когда stderr команды соответствует регулярному выражению?");
+tr!(RETRY_FOR_STDERR, "для stderr команды");
 
 tr!(CUSTOM_CMD_GUIDE_TITLE, "Команды оболочки для Деплойера");
 tr!(CUSTOM_CMD_GUIDE_1, "Использование команд для терминала в Деплойере очень простое.");
@@ -63,6 +75,8 @@ tr!(CMD_CHANGE_FAILURE_IGNORANCE, "Изменить игнорирование 
 tr!(CMD_CHANGE_VISIBILITY_AT_BUILD, "Изменить отображение команды на этапе сборки");
 tr!(CMD_CHANGE_VISIBILITY_ON_SUCC, "Изменить отображение команды при успешном выполнении");
 tr!(CMD_CHANGE_ON_FRESH, "Изменить выполнение команды только при свежих сборках");
+tr!(CMD_CHANGE_TIMEOUT, "Изменить время ожидания команды");
+tr!(CMD_CHANGE_RETRY, "Изменить политику повторного выполнения команды");
 
 tr!(CMDS_REORDER, "Упорядочить команды Действия:");
 
@@ -122,6 +136,22 @@ tr!(NOTE, "На заметку");
 tr!(VAR_NOTE, "если переменная - это секрет, то при сборке команда, содержащая секрет, не будет показана на экране.");
 tr!(VAR_IS_SECRET, "Является ли переменная секретом?");
 tr!(VAR_CONTENT, "Введите содержимое переменной:");
+tr!(VAR_VALUE_TYPE, "Выберите, откуда брать значение переменной:");
+tr!(VAR_VALUE_PLAIN, "Обычное значение");
+tr!(VAR_VALUE_FROM_ENV_FILE, "Файл `.env`");
+tr!(VAR_VALUE_FROM_ENV, "Переменная окружения");
+tr!(VAR_VALUE_FROM_CMD, "Вывод команды");
+tr!(VAR_ENV_FILE_PATH, "Введите путь к файлу `.env` (относительно папки проекта):");
+tr!(VAR_ENV_FILE_KEY, "Введите ключ внутри файла `.env`:");
+tr!(VAR_ENV_NAME, "Введите имя переменной окружения:");
+tr!(VAR_NO_ENV, "Нет переменной окружения `{1}` для переменной `{2}`!");
+tr!(VAR_NO_ENV_FILE_KEY, "Не удалось получить ключ `{1}` из файла `{2}` для переменной `{3}`!");
+tr!(VAR_CMD_FAILED, "Не удалось получить вывод команды `{1}` для переменной `{2}`!");
+tr!(VAR_KEY_NOT_FOUND, "ключ не найден");
+tr!(VAR_VALUE_SECRET, "Зашифрованный секрет Деплойера");
+tr!(VAR_SECRET_NAME, "Введите название секрета:");
+tr!(VAR_SECRET_NOTE, "не забудьте задать значение секрета с помощью `deployer secrets set {}`.");
+tr!(VAR_NO_SECRET, "Невозможно получить секрет `{1}` для переменной `{2}`!");
 
 tr!(VAR_EDIT, "Изменить переменную");
 tr!(VAR_SELECT_FC, "Выберите переменную для изменения");
@@ -166,6 +196,14 @@ tr!(EDIT_DESC, "Редактировать описание");
 tr!(EDIT_TAGS, "Редактировать теги");
 tr!(EDIT_PC_FILES, "Редактировать файлы и папки для удаления");
 tr!(EDIT_EXCL_TAG, "Редактировать эксклюзивную метку сборки");
+tr!(EDIT_DEFAULT_TIMEOUT, "Изменить время ожидания команд по умолчанию");
+tr!(EDIT_BUILD_SOURCE, "Изменить способ подготовки папок сборки");
+tr!(EDIT_PIPELINE_CACHES, "Изменить именованные кэши Пайплайна");
+tr!(EDIT_ACTIONS_NEEDS, "Изменить зависимости Действий");
+tr!(EDIT_ACTIONS_CONDITIONS, "Изменить условия выполнения Действий");
+tr!(EDIT_ACTIONS_CACHE, "Изменить входные и выходные файлы Действий для кэширования");
+tr!(EDIT_ON_FAILURE_ACTIONS, "Изменить Действия, выполняемые при неудаче Пайплайна");
+tr!(EDIT_ALWAYS_ACTIONS, "Изменить Действия, выполняемые после Пайплайна в любом случае");
 tr!(EDIT_PIPELINE_ACTIONS, "Редактировать Действия Пайплайна");
 tr!(EDIT_PROJECT_NAME, "Редактировать имя проекта");
 tr!(EDIT_PROJECT_PIPELINES, "Редактировать Пайплайны проекта");
@@ -190,6 +228,21 @@ tr!(PIPELINE_DESC, "Укажите описание Пайплайна:");
 tr!(PIPELINE_TAGS, "Напишите теги Пайплайна, если есть:");
 
 tr!(PIPELINE_SPECIFY_EXCL_TAG, "Укажите эксклюзивную метку сборки");
+tr!(PIPELINE_SPECIFY_DEFAULT_TIMEOUT, "Укажите время ожидания команд Пайплайна по умолчанию в секундах");
+tr!(PIPELINE_SPECIFY_BUILD_SOURCE, "Укажите, как подготавливать папки сборки для Пайплайна");
+tr!(BUILD_SOURCE_COPY, "Копировать файлы проекта");
+tr!(BUILD_SOURCE_WORKTREE, "Git worktree текущего коммита");
+tr!(BUILD_SOURCE_WORKTREE_WITH_CHANGES, "Git worktree текущего коммита с незакоммиченными изменениями");
+tr!(PIPELINE_SELECT_ACTION_FOR_NEEDS, "Выберите Действие для изменения его зависимостей");
+tr!(PIPELINE_ACTION_NEEDS, "Выберите Действия, которые должны быть выполнены до этого (ни одного - после предыдущего Действия)");
+tr!(PIPELINE_SELECT_ACTION_FOR_WHEN, "Выберите Действие для изменения его условия выполнения");
+tr!(PIPELINE_ACTION_WHEN, "Укажите условие (например, `fresh && branch == \"main\"`)");
+tr!(CACHE_NAME, "Введите название кэша (название существующего кэша - изменить или удалить кэш):");
+tr!(CACHE_PATHS, "Укажите кэшируемые файлы и папки (например, `target, node_modules`); ни одного - удалить кэш");
+tr!(CACHE_KEY_FILES, "Укажите файлы, от которых зависит ключ кэша (например, `Cargo.lock`)");
+tr!(PIPELINE_SELECT_ACTION_FOR_CACHE, "Выберите Действие для изменения его входных и выходных файлов");
+tr!(PIPELINE_ACTION_INPUTS, "Укажите входные файлы Действия шаблонами (например, `src/**/*.rs, Cargo.*`); ни одного - не кэшировать Действие");
+tr!(PIPELINE_ACTION_OUTPUTS, "Укажите выходные файлы и папки Действия (например, `target/release/app`)");
 
 tr!(PIPELINES_AVAILABLE, "Доступные Пайплайны в реестре Деплойера:");
 tr!(NO_PIPELINES, "В реестре Деплойера нет Пайплайнов.");
@@ -215,6 +268,15 @@ tr!(PIPELINE_SHORT_NAME_FOR_PROJECT, "Укажите короткое имя д
 tr!(PIPELINE_NEW_DEFAULT, "Установить этот Пайплайн Пайплайном по умолчанию? (y/n)");
 tr!(PIPELINE_NEW_DEFAULT_REPLACE, "Пайплайн `{}` уже установлен по умолчанию. Установить этот Пайплайн Пайплайном по умолчанию вместо него?");
 tr!(PIPELINE_DEFAULT_SET, "Пайплайн успешно установлен по умолчанию.");
+tr!(WITH_ANSWERS_INVALID, "Не удалось прочитать файл ответов по причине:");
+tr!(WITH_TAG_REQUIRED, "Для неинтерактивной настройки укажите `{short-name}@{version}` Пайплайна.");
+tr!(WITH_UNRESOLVED, "Нет ответов для следующих плейсхолдеров:");
+tr!(WITH_UNRESOLVED_PLACEHOLDER, "Нет ответа для плейсхолдера");
+tr!(WITH_ANSWERS_MISMATCH, "Ответы не соответствуют Пайплайну:");
+tr!(WITH_UNKNOWN_ACTION, "в Пайплайне нет такого Действия");
+tr!(WITH_UNKNOWN_PLACEHOLDER, "в командах Действия нет такого плейсхолдера");
+tr!(WITH_UNKNOWN_VARIABLE, "в проекте нет переменной `{}` (для значения используйте `{ \"literal\": \"...\" }`)");
+tr!(IN_CMD, "в команде");
 
 tr!(PIPELINE_SHORT_NAME_FOR_PROJECT_OVERRIDE, "Хотите ли вы перезаписать существующий Пайплайн `{}` для этого проекта? (y/n)");
 tr!(PIPELINE_EDIT, "Редактировать Пайплайн `{1}` - `{2}`");
@@ -224,14 +286,62 @@ tr!(PIPELINE_REORDER_ACTIONS, "Упорядочьте Действия в Пай
 tr!(PIPELINE_REMOVE, "Удалить Пайплайн `{1}` - `{2}`");
 
 tr!(STARTING_PIPELINE, "Запуск Пайплайна `{}`...");
+tr!(RUNNING_ON_FAILURE_ACTIONS, "Выполнение Действий при неудаче Пайплайна (`on_failure`)...");
+tr!(RUNNING_ALWAYS_ACTIONS, "Выполнение завершающих Действий (`always`)...");
 tr!(STARTING_ACTION, "Действие");
 tr!(ARTIFACTS_ENPLACED, "Артефакты размещены успешно.");
 tr!(INTERRUPT, "Пайплайн прерван. Нажмите `Enter` для продолжения");
 
 tr!(BUILD_PATH, "Путь сборки");
+tr!(BUILD_COMMIT, "Коммит");
+tr!(CACHE_RESTORED, "Кэш `{1}` восстановлен (ключ `{2}`)");
+tr!(CACHE_NOT_FOUND, "Кэш `{1}` с ключом `{2}` не найден, он будет сохранён после успешного выполнения Пайплайна");
+tr!(CACHE_SAVED, "Кэш `{1}` сохранён (ключ `{2}`)");
+tr!(CACHE_RESTORE_FAILED, "Не удалось восстановить кэш `{1}`, он будет сохранён заново после успешного выполнения Пайплайна: {2}");
+tr!(CACHE_SAVE_FAILED, "Не удалось сохранить кэш `{1}`: {2}");
 
 tr!(DONE, " завершено");
 tr!(GOT_ERROR, " завершилось с ошибкой!");
+tr!(ABORTED, " прервано пользователем!");
+tr!(INTERRUPTING, "Прерывание... Нажмите `Ctrl-C` ещё раз, чтобы принудительно завершить выполняемые команды.");
+tr!(TIMED_OUT, " не завершилось вовремя!");
+tr!(SKIPPED, " пропущено");
+tr!(CONDITION_NOT_MET, " пропущено");
+tr!(PREVIOUSLY_DONE, " уже выполнено");
+tr!(NOT_SELECTED, " не выбрано");
+tr!(PLANNED, " запланировано");
+tr!(CACHED, " восстановлено из кэша");
+tr!(ACTION_CACHE_HIT, "Входные файлы не изменились, выходные файлы Действия восстановлены из кэша (`{}`)");
+tr!(ACTION_CACHE_NO_OUTPUTS, "Действие создало не все выходные файлы, поэтому оно не кэшируется");
+tr!(DRY_RUN_COMMAND, "Будет выполнено {1} в папке `{2}`");
+tr!(DRY_RUN_SKIP_NOT_FRESH, "Будет пропущено `{}`: команда выполняется только при новых сборках");
+tr!(DRY_RUN_REMOVE, "Будет удалено `{}`");
+tr!(DRY_RUN_COPY, "Артефакт `{1}` будет скопирован в `{2}`");
+tr!(DRY_RUN_CONDITION, "Условие `{}` проверяется только во время сборки");
+tr!(DRY_RUN_INTERRUPT, "Здесь сборка будет приостановлена для подтверждения");
+tr!(DRY_RUN_BUILD_FOLDER, "Папка сборки: `{}`");
+tr!(DRY_RUN_NEW_BUILD_FOLDER, "Папка сборки: `{}` (новая сборка)");
+tr!(DRY_RUN_DONE, "Пробный запуск Пайплайна `{}` завершён, ничего не было выполнено.");
+tr!(CONDITION_IS_FALSE, "Условие `{}` не выполнено.");
+
+tr!(PIPELINE_DONE, "Пайплайн `{1}` завершён ({2}).");
+tr!(PIPELINE_FAILED, "Пайплайн `{1}` остановлен на Действии `{2}` ({3}).");
+tr!(SUMMARY_PIPELINE, "Пайплайн");
+tr!(SUMMARY_STATUS, "Статус");
+tr!(SUMMARY_ACTION, "Остановлен на");
+tr!(SUMMARY_ELAPSED, "Время");
+tr!(RUNNING, " выполняется");
+tr!(HISTORY_RUN, "Запуск");
+tr!(HISTORY_PROJECT, "Проект");
+tr!(HISTORY_STARTED, "Начало");
+tr!(HISTORY_BUILD_FOLDER, "Папка сборки");
+tr!(HISTORY_EMPTY, "Запусков пока нет.");
+tr!(RUN_NOT_FOUND, "Запуск `{}` в истории не найден!");
+tr!(GC_REMOVED, "Удалено `{1}` ({2})");
+tr!(GC_WOULD_REMOVE, "Будет удалено `{1}` ({2})");
+tr!(GC_RECLAIMED, "Освобождено {}.");
+tr!(GC_WOULD_RECLAIM, "Будет освобождено {}.");
+tr!(GC_NOTHING, "Очищать нечего.");
 
 // Project
 tr!(PROJECT_NAME, "Введите название проекта:");
@@ -259,3 +369,13 @@ tr!(ADD_NEW_INPLACEMENT_FIRST, "Хотите ли вы создать разме
 tr!(ADD_NEW_INPLACEMENT_SECOND, "Добавить ещё одно размещение артефакта?");
 
 tr!(INIT_SUCC, "Настройка завершена. Не забудьте назначить минимум один Пайплайн проекту для сборки или развёртывания!");
+
+// Secrets
+tr!(SECRETS_PASSPHRASE, "Введите пароль от секретов Деплойера:");
+tr!(SECRETS_PASSPHRASE_CONFIRM, "Подтвердите пароль:");
+tr!(SECRETS_WRONG_PASSPHRASE, "Невозможно открыть секреты Деплойера: неверный пароль или ключевой файл!");
+tr!(SECRET_VALUE, "Введите значение секрета:");
+tr!(SECRET_NOT_FOUND, "Секрет `{1}` для проекта `{2}` не найден!");
+tr!(SECRETS_EMPTY, "У этого проекта нет секретов.");
+tr!(SECRET_SAVED, "Секрет `{}` сохранён.");
+tr!(SECRET_REMOVED, "Секрет `{}` удалён.");
@@ -0,0 +1,110 @@
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::i18n;
+use crate::EXIT_USER_ABORT;
+
+/// Было ли выполнение прервано пользователем.
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+/// Завершать ли выполнение корректно (во время сборки) вместо немедленного выхода.
+static GRACEFUL: AtomicBool = AtomicBool::new(false);
+/// Запущенные команды: идентификатор процесса и признак отдельной группы процессов.
+static CHILDREN: Mutex<Vec<(u32, bool)>> = Mutex::new(vec![]);
+
+/// Устанавливает обработчик `Ctrl-C`.
+/// 
+/// Во время сборки первое прерывание передаётся запущенным командам (`SIGINT`), после чего сборка корректно завершается;
+/// повторное прерывание принудительно завершает команды (`SIGKILL`). В остальных случаях Деплойер завершается сразу.
+pub(crate) fn set_handler() -> anyhow::Result<()> {
+  ctrlc::set_handler(move || {
+    if !GRACEFUL.load(Ordering::SeqCst) {
+      println!("\nInterrupted");
+      std::process::exit(EXIT_USER_ABORT);
+    }
+    
+    let repeated = INTERRUPTED.swap(true, Ordering::SeqCst);
+    if !repeated { eprintln!("\n{}", i18n::INTERRUPTING); }
+    
+    let children = CHILDREN.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+    for (pid, own_group) in children.iter() {
+      // Команды из группы процессов Деплойера уже получили `SIGINT` от терминала
+      let (pid, signal) = match (repeated, own_group) {
+        (true, true) => (-(*pid as libc::pid_t), libc::SIGKILL),
+        (true, false) => (*pid as libc::pid_t, libc::SIGKILL),
+        (false, true) => (-(*pid as libc::pid_t), libc::SIGINT),
+        (false, false) => continue,
+      };
+      // SAFETY: отправка сигнала процессу (группе процессов), порождённому Деплойером.
+      unsafe { libc::kill(pid, signal); }
+    }
+  })?;
+  
+  Ok(())
+}
+
+/// Включает корректное завершение при прерывании.
+pub(crate) fn handle_gracefully() {
+  GRACEFUL.store(true, Ordering::SeqCst);
+}
+
+/// Проверяет, было ли выполнение прервано пользователем.
+pub(crate) fn interrupted() -> bool {
+  INTERRUPTED.load(Ordering::SeqCst)
+}
+
+/// Отмечает выполнение прерванным, если прерывание получила команда, а не Деплойер (см. `TerminalGuard`).
+pub(crate) fn mark_interrupted() {
+  if GRACEFUL.load(Ordering::SeqCst) && !INTERRUPTED.swap(true, Ordering::SeqCst) { eprintln!("\n{}", i18n::INTERRUPTING); }
+}
+
+/// Регистрирует запущенную команду на время её выполнения.
+pub(crate) struct ChildGuard(u32);
+
+impl ChildGuard {
+  pub(crate) fn new(pid: u32, own_group: bool) -> Self {
+    CHILDREN.lock().unwrap_or_else(std::sync::PoisonError::into_inner).push((pid, own_group));
+    // Прерывание могло произойти до регистрации команды
+    // SAFETY: см. `set_handler`.
+    if own_group && interrupted() { unsafe { libc::kill(-(pid as libc::pid_t), libc::SIGINT); } }
+    Self(pid)
+  }
+}
+
+impl Drop for ChildGuard {
+  fn drop(&mut self) {
+    CHILDREN.lock().unwrap_or_else(std::sync::PoisonError::into_inner).retain(|(pid, _)| *pid != self.0);
+  }
+}
+
+/// Передаёт терминал группе процессов команды на время её выполнения.
+/// 
+/// Команда, запущенная в отдельной группе процессов, иначе не сможет читать ввод с терминала; при этом `Ctrl-C`
+/// получает только группа процессов команды.
+pub(crate) struct TerminalGuard(Option<libc::pid_t>);
+
+impl TerminalGuard {
+  pub(crate) fn new(pid: u32) -> Self {
+    // SAFETY: вызовы лишь читают и меняют группу процессов, которой принадлежит терминал.
+    unsafe {
+      // Терминал передаётся, только если Деплойер выполняется на переднем плане
+      if libc::isatty(libc::STDIN_FILENO) != 1 || libc::tcgetpgrp(libc::STDIN_FILENO) != libc::getpgrp() { return Self(None) }
+      let own = libc::getpgrp();
+      if libc::tcsetpgrp(libc::STDIN_FILENO, pid as libc::pid_t) != 0 { return Self(None) }
+      // Команда могла обратиться к терминалу до его передачи и быть остановлена
+      libc::kill(-(pid as libc::pid_t), libc::SIGCONT);
+      Self(Some(own))
+    }
+  }
+}
+
+impl Drop for TerminalGuard {
+  fn drop(&mut self) {
+    let Some(own) = self.0 else { return };
+    // SAFETY: Деплойер возвращает себе терминал; `SIGTTOU` игнорируется, поскольку Деплойер сейчас в фоновой группе.
+    unsafe {
+      let handler = libc::signal(libc::SIGTTOU, libc::SIG_IGN);
+      libc::tcsetpgrp(libc::STDIN_FILENO, own);
+      libc::signal(libc::SIGTTOU, handler);
+    }
+  }
+}
@@ -1,8 +1,8 @@
-#![feature(let_chains, if_let_guard, once_wait, string_from_utf8_lossy_owned)]
+#![feature(let_chains, if_let_guard, once_wait)]
 #![warn(clippy::todo, clippy::unimplemented)]
 #![deny(warnings)]
 
-#[cfg(feature = "tests")]
+#[cfg(any(test, feature = "tests"))]
 mod tests;
 
 mod cmd;
@@ -12,6 +12,12 @@ mod utils;
 
 mod init;
 mod build;
+mod history;
+mod gc;
+mod report;
+mod action_cache;
+mod interrupt;
+mod secrets;
 
 mod actions;
 mod pipelines;
@@ -33,7 +39,11 @@ use crate::utils::get_current_working_dir;
 use crate::tests::tests;
 
 use crate::init::init;
-use crate::build::{build, clean_builds};
+use crate::build::{build, clean_builds, outcomes_exit_code};
+use crate::history::{history, logs};
+use crate::gc::gc;
+use crate::secrets::{secrets, ProjectSecrets};
+use crate::utils::ConfigError;
 
 use clap::Parser;
 use dirs::{config_dir, cache_dir};
@@ -45,12 +55,23 @@ static GLOBAL: MiMalloc = MiMalloc;
 static PROJECT_CONF: &str = "deploy-config.json";
 static GLOBAL_CONF: &str = "deploy-global.json";
 static BUILD_CACHE_LIST: &str = "deploy-builds.json";
+static SECRETS_VAULT: &str = "deploy-secrets.json";
+static BUILD_HISTORY: &str = "deploy-history.json";
 
 pub(crate) static CACHE_DIR: &str = "deploy-cache";
+pub(crate) static ACTION_CACHE_DIR: &str = "deploy-action-cache";
+pub(crate) static NAMED_CACHES_DIR: &str = "deploy-named-caches";
 pub(crate) static LOGS_DIR: &str = "logs";
 
 pub(crate) static ARTIFACTS_DIR: &str = "artifacts";
 
+/// Одно из Действий Пайплайна завершилось с ошибкой.
+pub(crate) static EXIT_ACTION_FAILURE: i32 = 2;
+/// Конфигурация проекта или аргументы запуска некорректны.
+pub(crate) static EXIT_CONFIG_ERROR: i32 = 3;
+/// Выполнение Пайплайна прервано пользователем.
+pub(crate) static EXIT_USER_ABORT: i32 = 4;
+
 #[cfg(not(unix))]
 compile_error!("`deployer` can't work with non-Unix systems.");
 
@@ -65,10 +86,7 @@ fn main() {
     std::process::exit(1);
   }));
   
-  ctrlc::set_handler(move || {
-    println!("\nInterrupted");
-    std::process::exit(0);
-  }).expect("Error setting Ctrl-C handler");
+  interrupt::set_handler().expect("Error setting Ctrl-C handler");
   
   let args = Cli::parse();
   
@@ -93,6 +111,8 @@ fn main() {
     path.join(args.config_folder.as_ref().unwrap())
   };
   
+  let key_file = args.secrets_key_file.clone();
+  
   // Чтение конфигов
   let mut globals = read::<DeployerGlobalConfig>(&config_folder, GLOBAL_CONF);
   let mut config = read::<DeployerProjectOptions>(&get_current_working_dir().unwrap(), PROJECT_CONF);
@@ -134,7 +154,13 @@ fn main() {
       write(get_current_working_dir().unwrap(), PROJECT_CONF, &config);
     },
     DeployerExecType::With(args) => {
-      assign_pipeline_to_project(&mut globals, &mut config, &args).unwrap();
+      match assign_pipeline_to_project(&mut globals, &mut config, &args) {
+        Err(e) if e.is::<ConfigError>() => {
+          eprintln!("{}", e);
+          std::process::exit(EXIT_CONFIG_ERROR);
+        },
+        res => res.unwrap(),
+      }
       write(&config_folder, GLOBAL_CONF, &globals);
       write(get_current_working_dir().unwrap(), PROJECT_CONF, &config);
     },
@@ -145,9 +171,34 @@ fn main() {
       write(get_current_working_dir().unwrap(), PROJECT_CONF, &config);
     },
     DeployerExecType::Build(args) => {
-      build(&mut config, &mut builds, &cache_folder, &args).unwrap();
+      let project_secrets = ProjectSecrets::new(&config_folder, &config.project_name, key_file.as_deref());
+      let res = build(&mut config, &mut builds, &cache_folder, &project_secrets, globals.log_format, &args);
+      write(&cache_folder, BUILD_CACHE_LIST, &builds);
+      
+      let outcomes = match res {
+        Err(e) if e.is::<ConfigError>() => {
+          eprintln!("{}", e);
+          std::process::exit(EXIT_CONFIG_ERROR);
+        },
+        res => res.unwrap(),
+      };
+      
+      let code = outcomes_exit_code(&outcomes);
+      if code != 0 { std::process::exit(code); }
+    },
+    DeployerExecType::Secrets(args) => {
+      if config == Default::default() {
+        eprintln!("{}", i18n::CFG_INVALID);
+        std::process::exit(EXIT_CONFIG_ERROR);
+      }
+      secrets(&config_folder, &config.project_name, key_file.as_deref(), &args).unwrap();
+    },
+    DeployerExecType::Gc(args) => {
+      gc(&mut builds, &cache_folder, &args).unwrap();
       write(&cache_folder, BUILD_CACHE_LIST, &builds);
     },
+    DeployerExecType::History(args) => history(&cache_folder, &args).unwrap(),
+    DeployerExecType::Logs(args) => logs(&cache_folder, &config.project_name, &args).unwrap(),
     DeployerExecType::Clean(args) => {
       clean_builds(&config, &mut builds, &cache_folder, &args).unwrap();
       write(&cache_folder, BUILD_CACHE_LIST, &builds);
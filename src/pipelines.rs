@@ -1,20 +1,29 @@
+use chrono::{DateTime, Local};
 use colored::Colorize;
 use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
 use std::process::exit;
+use std::time::{Duration, Instant};
 
+use crate::action_cache;
 use crate::actions::{DescribedAction, Action, new_action};
 use crate::build::enplace_artifacts;
 use crate::cmd::{NewActionArgs, NewPipelineArgs, CatPipelineArgs, WithPipelineArgs};
-use crate::configs::{DeployerGlobalConfig, DeployerProjectOptions};
+use crate::configs::{DeployerGlobalConfig, DeployerProjectOptions, WithPipelineAnswers};
 use crate::entities::{
+  condition::Condition,
+  custom_command::{specify_timeout, ExitCodes},
   environment::BuildEnvironment,
   info::{PipelineInfo, info2str_simple, info2str, str2info},
+  named_cache::{restore_caches, save_caches, NamedCache},
   traits::{EditExtended, Execute},
 };
 use crate::hmap;
+use crate::history::{self, RunRecord};
 use crate::i18n;
-use crate::rw::{read_checked, generate_build_log_filepath, build_log};
-use crate::utils::tags_custom_type;
+use crate::interrupt;
+use crate::rw::{read_checked, generate_build_log_filepath, BuildLog, LogEvent};
+use crate::utils::{build_relative_path, tags_custom_type, CommandTimedOut, ConfigError};
 use crate::ARTIFACTS_DIR;
 
 #[derive(Deserialize, Serialize, PartialEq, Clone, Debug)]
@@ -39,6 +48,32 @@ pub(crate) struct DescribedPipeline {
   /// Если зависит, то пайплайн будет выполняться в папках с указанным тегом сборки.
   #[serde(skip_serializing_if = "Option::is_none")]
   pub(crate) exclusive_exec_tag: Option<String>,
+  /// Время ожидания (в секундах) по умолчанию для команд Действий, у которых оно не указано.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub(crate) default_timeout: Option<u64>,
+  /// Способ подготовки папки сборки; если не установлен, файлы проекта копируются.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub(crate) build_source: Option<BuildSource>,
+  /// Именованные кэши, восстанавливаемые в папку сборки перед выполнением Пайплайна.
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub(crate) caches: Vec<NamedCache>,
+  /// Действия, выполняемые после неудачного выполнения Пайплайна (например, откат или уведомление).
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub(crate) on_failure: Vec<DescribedAction>,
+  /// Действия, выполняемые после выполнения Пайплайна в любом случае.
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub(crate) always: Vec<DescribedAction>,
+}
+
+/// Способ подготовки папки сборки.
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq)]
+pub(crate) enum BuildSource {
+  /// Файлы проекта копируются в папку сборки.
+  Copy,
+  /// Папка сборки - `git worktree` текущего коммита проекта.
+  Worktree,
+  /// Папка сборки - `git worktree` текущего коммита проекта с незакоммиченными изменениями.
+  WorktreeWithChanges,
 }
 
 impl DescribedPipeline {
@@ -59,6 +94,7 @@ impl DescribedPipeline {
     let selected_actions_ordered = reorder_actions(selected_actions_unordered)?;
     
     let exclusive_exec_tag = Text::new(&format!("{} {}:", i18n::PIPELINE_SPECIFY_EXCL_TAG, i18n::OR_HIT_ESC)).prompt_skippable()?;
+    let default_timeout = specify_timeout(i18n::PIPELINE_SPECIFY_DEFAULT_TIMEOUT, None)?;
     
     let described_pipeline = DescribedPipeline {
       title: name,
@@ -68,6 +104,11 @@ impl DescribedPipeline {
       actions: selected_actions_ordered,
       default: None,
       exclusive_exec_tag,
+      default_timeout,
+      build_source: None,
+      caches: vec![],
+      on_failure: vec![],
+      always: vec![],
     };
     
     Ok(described_pipeline)
@@ -80,6 +121,14 @@ impl DescribedPipeline {
       i18n::EDIT_DESC,
       i18n::EDIT_TAGS,
       i18n::EDIT_EXCL_TAG,
+      i18n::EDIT_DEFAULT_TIMEOUT,
+      i18n::EDIT_BUILD_SOURCE,
+      i18n::EDIT_PIPELINE_CACHES,
+      i18n::EDIT_ACTIONS_NEEDS,
+      i18n::EDIT_ACTIONS_CONDITIONS,
+      i18n::EDIT_ACTIONS_CACHE,
+      i18n::EDIT_ON_FAILURE_ACTIONS,
+      i18n::EDIT_ALWAYS_ACTIONS,
     ];
     
     while let Some(action) = inquire::Select::new(
@@ -101,12 +150,219 @@ impl DescribedPipeline {
             &format!("{} {}:", i18n::PIPELINE_SPECIFY_EXCL_TAG, i18n::OR_HIT_ESC)
           ).with_initial_value(self.exclusive_exec_tag.as_ref().unwrap()).prompt_skippable()?
         },
+        i18n::EDIT_DEFAULT_TIMEOUT => self.default_timeout = specify_timeout(i18n::PIPELINE_SPECIFY_DEFAULT_TIMEOUT, self.default_timeout)?,
+        i18n::EDIT_BUILD_SOURCE => self.edit_build_source_from_prompt()?,
+        i18n::EDIT_PIPELINE_CACHES => self.edit_caches_from_prompt()?,
+        i18n::EDIT_ACTIONS_NEEDS => self.edit_needs_from_prompt()?,
+        i18n::EDIT_ACTIONS_CONDITIONS => self.edit_condition_from_prompt()?,
+        i18n::EDIT_ACTIONS_CACHE => self.edit_cache_from_prompt()?,
+        i18n::EDIT_ON_FAILURE_ACTIONS => self.on_failure.edit_from_prompt(globals)?,
+        i18n::EDIT_ALWAYS_ACTIONS => self.always.edit_from_prompt(globals)?,
         _ => {},
       }
     }
     
     Ok(())
   }
+  
+  /// Изменяет способ подготовки папки сборки.
+  fn edit_build_source_from_prompt(&mut self) -> anyhow::Result<()> {
+    let options = vec![i18n::BUILD_SOURCE_COPY, i18n::BUILD_SOURCE_WORKTREE, i18n::BUILD_SOURCE_WORKTREE_WITH_CHANGES];
+    let current = match self.build_source {
+      None | Some(BuildSource::Copy) => 0,
+      Some(BuildSource::Worktree) => 1,
+      Some(BuildSource::WorktreeWithChanges) => 2,
+    };
+    
+    self.build_source = match inquire::Select::new(i18n::PIPELINE_SPECIFY_BUILD_SOURCE, options).with_starting_cursor(current).raw_prompt()?.index {
+      1 => Some(BuildSource::Worktree),
+      2 => Some(BuildSource::WorktreeWithChanges),
+      _ => None,
+    };
+    
+    Ok(())
+  }
+  
+  /// Добавляет, изменяет или удаляет именованный кэш Пайплайна.
+  fn edit_caches_from_prompt(&mut self) -> anyhow::Result<()> {
+    let names = self.caches.iter().map(|c| c.name.as_str()).collect::<Vec<_>>().join(", ");
+    let mut prompt = inquire::Text::new(i18n::CACHE_NAME).with_validator(|s: &str| Ok(
+      match (NamedCache { name: s.trim().to_owned(), paths: vec![], key_files: vec![] }).validate() {
+        Ok(_) => inquire::validator::Validation::Valid,
+        Err(e) => inquire::validator::Validation::Invalid(e.into()),
+      }
+    ));
+    if !names.is_empty() { prompt = prompt.with_help_message(&names); }
+    let name = prompt.prompt()?.trim().to_owned();
+    
+    let position = self.caches.iter().position(|c| c.name.eq(&name));
+    match (NamedCache::new_from_prompt(&name, position.map(|i| &self.caches[i]))?, position) {
+      (Some(cache), Some(i)) => self.caches[i] = cache,
+      (Some(cache), None) => self.caches.push(cache),
+      (None, Some(i)) => { self.caches.remove(i); },
+      (None, None) => {},
+    }
+    
+    Ok(())
+  }
+  
+  /// Изменяет список Действий, после которых выполняется выбранное Действие.
+  fn edit_needs_from_prompt(&mut self) -> anyhow::Result<()> {
+    let titles = self.actions.iter().map(|a| a.title.to_owned()).collect::<Vec<_>>();
+    let selected = inquire::Select::new(i18n::PIPELINE_SELECT_ACTION_FOR_NEEDS, titles.clone()).raw_prompt()?.index;
+    
+    let others = titles.iter().enumerate().filter(|(i, _)| *i != selected).map(|(_, t)| t.to_owned()).collect::<Vec<_>>();
+    let current = self.actions[selected].needs.clone();
+    let defaults = others
+      .iter()
+      .enumerate()
+      .filter(|(_, t)| current.as_ref().is_some_and(|needs| needs.contains(t)))
+      .map(|(i, _)| i)
+      .collect::<Vec<_>>();
+    
+    let needs = inquire::MultiSelect::new(i18n::PIPELINE_ACTION_NEEDS, others).with_default(&defaults).prompt()?;
+    self.actions[selected].needs = if needs.is_empty() { None } else { Some(needs) };
+    
+    if let Err(e) = self.dependencies() {
+      println!("{}", e.to_string().red());
+      self.actions[selected].needs = current;
+    }
+    
+    Ok(())
+  }
+  
+  /// Изменяет условие выполнения выбранного Действия.
+  fn edit_condition_from_prompt(&mut self) -> anyhow::Result<()> {
+    let titles = self.actions.iter().map(|a| a.title.to_owned()).collect::<Vec<_>>();
+    let selected = inquire::Select::new(i18n::PIPELINE_SELECT_ACTION_FOR_WHEN, titles).raw_prompt()?.index;
+    
+    let message = format!("{} {}:", i18n::PIPELINE_ACTION_WHEN, i18n::OR_HIT_ESC);
+    let mut prompt = inquire::Text::new(&message).with_validator(|s: &str| Ok(match Condition::parse(s) {
+      Ok(_) => inquire::validator::Validation::Valid,
+      Err(e) => inquire::validator::Validation::Invalid(e.into()),
+    }));
+    if let Some(when) = &self.actions[selected].when { prompt = prompt.with_initial_value(when); }
+    
+    self.actions[selected].when = prompt.prompt_skippable()?.filter(|s| !s.trim().is_empty());
+    
+    Ok(())
+  }
+  
+  /// Изменяет входные и выходные файлы выбранного Действия для кэширования.
+  fn edit_cache_from_prompt(&mut self) -> anyhow::Result<()> {
+    let titles = self.actions.iter().map(|a| a.title.to_owned()).collect::<Vec<_>>();
+    let selected = inquire::Select::new(i18n::PIPELINE_SELECT_ACTION_FOR_CACHE, titles).raw_prompt()?.index;
+    let action = &mut self.actions[selected];
+    
+    let joined = action.inputs.as_ref().map(|i| i.join(", ")).unwrap_or_default();
+    let inputs = tags_custom_type(i18n::PIPELINE_ACTION_INPUTS, if joined.is_empty() { None } else { Some(joined.as_str()) }).prompt()?;
+    if inputs.is_empty() {
+      action.inputs = None;
+      action.outputs = None;
+      return Ok(())
+    }
+    
+    let joined = action.outputs.as_ref().map(|o| o.join(", ")).unwrap_or_default();
+    let outputs = tags_custom_type(i18n::PIPELINE_ACTION_OUTPUTS, if joined.is_empty() { None } else { Some(joined.as_str()) })
+      .with_validator(|outputs: &Vec<String>| Ok(match outputs.iter().try_for_each(|o| build_relative_path(o).map(|_| ())) {
+        Ok(_) => inquire::validator::Validation::Valid,
+        Err(e) => inquire::validator::Validation::Invalid(e.into()),
+      }))
+      .prompt()?;
+    
+    action.inputs = Some(inputs);
+    action.outputs = if outputs.is_empty() { None } else { Some(outputs) };
+    
+    Ok(())
+  }
+  
+  /// Подготавливаются ли папки сборки Пайплайна как `git worktree`.
+  pub(crate) fn uses_worktree(&self) -> bool {
+    matches!(self.build_source, Some(BuildSource::Worktree | BuildSource::WorktreeWithChanges))
+  }
+  
+  /// Все Действия Пайплайна, включая Действия `on_failure` и `always`.
+  pub(crate) fn all_actions(&self) -> impl Iterator<Item = &DescribedAction> {
+    self.actions.iter().chain(self.on_failure.iter()).chain(self.always.iter())
+  }
+  
+  /// Проверяет условия и зависимости Действий Пайплайна.
+  pub(crate) fn validate(&self) -> anyhow::Result<()> {
+    for action in self.all_actions() {
+      if let Some(when) = &action.when {
+        Condition::parse(when).map_err(|e| anyhow::anyhow!("Action `{}` of `{}` Pipeline: {}", action.title, self.title, e))?;
+      }
+      for output in action.outputs.iter().flatten() {
+        build_relative_path(output).map_err(|e| anyhow::anyhow!("Action `{}` of `{}` Pipeline: {}", action.title, self.title, e))?;
+      }
+    }
+    for (i, cache) in self.caches.iter().enumerate() {
+      cache.validate().map_err(|e| anyhow::anyhow!("Cache `{}` of `{}` Pipeline: {}", cache.name, self.title, e))?;
+      if self.caches[..i].iter().any(|c| c.name.eq(&cache.name)) {
+        return Err(anyhow::anyhow!("The `{}` Pipeline has several caches named `{}`!", self.title, cache.name))
+      }
+    }
+    self.dependencies()?;
+    
+    Ok(())
+  }
+  
+  /// Зависимости Действий Пайплайна: для каждого Действия - индексы Действий, которые должны быть выполнены до него.
+  /// 
+  /// Действие без `needs` зависит от предыдущего Действия. Проверяет, что все зависимости существуют
+  /// и однозначны и что среди них нет циклов.
+  pub(crate) fn dependencies(&self) -> anyhow::Result<Vec<Vec<usize>>> {
+    let mut dependencies = vec![];
+    
+    for (i, action) in self.actions.iter().enumerate() {
+      let Some(needs) = &action.needs else {
+        dependencies.push(if i == 0 { vec![] } else { vec![i - 1] });
+        continue
+      };
+      
+      let mut action_deps = vec![];
+      for title in needs {
+        let found = self.actions.iter().enumerate().filter(|(_, a)| a.title.eq(title)).map(|(j, _)| j).collect::<Vec<_>>();
+        match found.as_slice() {
+          [j] => action_deps.push(*j),
+          [] => return Err(anyhow::anyhow!(
+            "Action `{}` of `{}` Pipeline needs `{}` Action, but there is no such Action!", action.title, self.title, title,
+          )),
+          _ => return Err(anyhow::anyhow!(
+            "Action `{}` of `{}` Pipeline needs `{}` Action, but there are several Actions with such title!", action.title, self.title, title,
+          )),
+        }
+      }
+      dependencies.push(action_deps);
+    }
+    
+    // Проверка на циклы: Действия без невыполненных зависимостей последовательно исключаются из графа
+    let mut remaining = dependencies.iter().map(|deps| deps.len()).collect::<Vec<_>>();
+    let mut ready = (0..self.actions.len()).filter(|i| remaining[*i] == 0).collect::<Vec<_>>();
+    let mut resolved = 0;
+    while let Some(i) = ready.pop() {
+      resolved += 1;
+      for (j, deps) in dependencies.iter().enumerate() {
+        for _ in deps.iter().filter(|d| **d == i) {
+          remaining[j] -= 1;
+          if remaining[j] == 0 { ready.push(j); }
+        }
+      }
+    }
+    if resolved != self.actions.len() {
+      // Действия, лишь зависящие от цикла, но не входящие в него, исключаются из сообщения
+      let mut cycled = (0..self.actions.len()).filter(|i| remaining[*i] != 0).collect::<Vec<_>>();
+      loop {
+        let snapshot = cycled.clone();
+        cycled.retain(|i| snapshot.iter().any(|j| dependencies[*j].contains(i)));
+        if cycled.len() == snapshot.len() { break }
+      }
+      let cycled = cycled.iter().map(|i| format!("`{}`", self.actions[*i].title)).collect::<Vec<_>>();
+      return Err(anyhow::anyhow!("Actions {} of `{}` Pipeline have cyclic dependencies!", cycled.join(", "), self.title))
+    }
+    
+    Ok(dependencies)
+  }
 }
 
 /// Перечисляет все доступные пайплайны.
@@ -138,6 +394,7 @@ pub(crate) fn new_pipeline(
     let pipeline = read_checked::<DescribedPipeline>(from_file).map_err(|e| {
       panic!("Can't read provided Pipeline file due to: {}", e);
     }).unwrap();
+    pipeline.validate()?;
     globals.pipelines_registry.insert(info2str_simple(&pipeline.info), pipeline);
     return Ok(())
   }
@@ -331,7 +588,15 @@ pub(crate) fn assign_pipeline_to_project(
   config: &mut DeployerProjectOptions,
   args: &WithPipelineArgs,
 ) -> anyhow::Result<()> {
-  if *config == Default::default() { panic!("{}", i18n::CFG_INVALID); }
+  if *config == Default::default() { return Err(ConfigError(i18n::CFG_INVALID.to_string()).into()) }
+  
+  let answers = if let Some(answers_file) = &args.answers {
+    Some(read_checked::<WithPipelineAnswers>(answers_file).map_err(|e| anyhow::anyhow!("{} {}", i18n::WITH_ANSWERS_INVALID, e))?)
+  } else if args.defaults {
+    Some(WithPipelineAnswers::default())
+  } else {
+    None
+  };
   
   let mut pipeline = if let Some(tag) = &args.tag {
     globals
@@ -339,6 +604,8 @@ pub(crate) fn assign_pipeline_to_project(
       .get(tag)
       .ok_or_else(|| anyhow::anyhow!(i18n::NO_SUCH_PIPELINE))?
       .clone()
+  } else if answers.is_some() {
+    return Err(anyhow::anyhow!(i18n::WITH_TAG_REQUIRED))
   } else if !globals.pipelines_registry.is_empty() {
     const NEW_PIPELINE: &str = i18n::PIPELINE_SPECIFY_ANOTHER;
     
@@ -366,12 +633,35 @@ pub(crate) fn assign_pipeline_to_project(
     DescribedPipeline::new_from_prompt(globals)?
   };
   
-  for action in &mut pipeline.actions {
-    *action = action.prompt_setup_for_project(&config.langs, &config.deploy_toolkit, &config.targets, &config.variables, &config.artifacts)?;
+  if let Some(answers) = &answers {
+    let mismatches = answers.check(&pipeline, &config.variables);
+    if !mismatches.is_empty() {
+      return Err(ConfigError(format!("{}\n{}", i18n::WITH_ANSWERS_MISMATCH, mismatches.join("\n"))).into())
+    }
+    
+    let unresolved = pipeline
+      .all_actions()
+      .flat_map(|a| a.commands().into_iter().map(move |c| (a, c)))
+      .flat_map(|(a, c)| {
+        c.unresolved_placeholders(&a.info, &config.variables, answers)
+          .into_iter()
+          .map(move |p| format!("• `{}`: `{}` ({} `{}`)", info2str_simple(&a.info), p, i18n::IN_CMD, c.bash_c))
+      })
+      .collect::<Vec<_>>();
+    
+    if !unresolved.is_empty() {
+      return Err(ConfigError(format!("{}\n{}", i18n::WITH_UNRESOLVED, unresolved.join("\n"))).into())
+    }
+  }
+  
+  for action in pipeline.actions.iter_mut().chain(pipeline.on_failure.iter_mut()).chain(pipeline.always.iter_mut()) {
+    *action = action.prompt_setup_for_project(&config.langs, &config.deploy_toolkit, &config.targets, &config.variables, &config.artifacts, answers.as_ref())?;
   }
   
   let short_name = if let Some(short_name) = args.r#as.as_ref() {
     short_name.to_owned()
+  } else if let Some(answers) = &answers {
+    answers.short_name.clone().unwrap_or_else(|| pipeline.info.short_name.clone())
   } else {
     inquire::Text::new(i18n::PIPELINE_SHORT_NAME_FOR_PROJECT).prompt()?
   };
@@ -379,6 +669,24 @@ pub(crate) fn assign_pipeline_to_project(
   pipeline.desc = format!(r#"{} `{}`.{}{}"#, i18n::GOT_FROM, pipeline.title, if pipeline.desc.is_empty() { "" } else { " " }, pipeline.desc);
   pipeline.title = short_name.clone();
   
+  if let Some(answers) = &answers {
+    let has_default = config.pipelines.iter().any(|p| p.default.is_some_and(|v| v) && p.title.as_str() != short_name);
+    if answers.default.unwrap_or(!has_default) {
+      config.pipelines.iter_mut().for_each(|p| p.default = None);
+      pipeline.default = Some(true);
+    }
+    
+    remove_old_pipeline(config, &short_name);
+    config.pipelines.push(pipeline);
+    
+    if !answers.order.is_empty() {
+      config.pipelines.sort_by_key(|p| answers.order.iter().position(|t| t.as_str() == p.title.as_str()).unwrap_or(answers.order.len()));
+    }
+    
+    println!("{}", i18n::PIPELINE_DEFAULT_SET);
+    return Ok(())
+  }
+  
   if specify_short_name(config, &mut pipeline.title).is_err() { return Ok(()) };
   
   if let Some(old_default) = config.pipelines.iter_mut().find(|p| p.default.is_some_and(|v| v)) {
@@ -552,99 +860,668 @@ impl EditExtended<DeployerGlobalConfig> for Vec<DescribedPipeline> {
   }
 }
 
+/// Статус выполнения Действия.
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Debug)]
+pub(crate) enum ActionStatus {
+  /// Действие выполнено успешно.
+  Done,
+  /// Действие завершилось с ошибкой.
+  Failed,
+  /// Выполнение прервано пользователем.
+  Aborted,
+  /// Команда Действия не завершилась за отведённое время.
+  TimedOut,
+  /// Действие не выполнялось, поскольку не были выполнены Действия, от которых оно зависит.
+  Skipped,
+  /// Действие не выполнялось, поскольку не выполнено его условие `when`.
+  ConditionNotMet,
+  /// Действие не выполнялось, поскольку было успешно выполнено при предыдущем запуске (`--resume`).
+  PreviouslyDone,
+  /// Действие не выполнялось, поскольку не было выбрано пользователем (`--from`, `--only`).
+  NotSelected,
+  /// Действие не выполнялось: пробный запуск (`--dry-run`).
+  Planned,
+  /// Действие не выполнялось: его выходные файлы восстановлены из кэша Действий.
+  Cached,
+}
+
+impl ActionStatus {
+  pub(crate) fn describe(&self) -> &'static str {
+    match self {
+      ActionStatus::Done => i18n::DONE,
+      ActionStatus::Failed => i18n::GOT_ERROR,
+      ActionStatus::Aborted => i18n::ABORTED,
+      ActionStatus::TimedOut => i18n::TIMED_OUT,
+      ActionStatus::Skipped => i18n::SKIPPED,
+      ActionStatus::ConditionNotMet => i18n::CONDITION_NOT_MET,
+      ActionStatus::PreviouslyDone => i18n::PREVIOUSLY_DONE,
+      ActionStatus::NotSelected => i18n::NOT_SELECTED,
+      ActionStatus::Planned => i18n::PLANNED,
+      ActionStatus::Cached => i18n::CACHED,
+    }
+  }
+  
+  /// Позволяет ли статус Действия продолжить выполнение Пайплайна.
+  pub(crate) fn is_ok(&self) -> bool {
+    matches!(
+      self,
+      ActionStatus::Done | ActionStatus::ConditionNotMet | ActionStatus::PreviouslyDone | ActionStatus::NotSelected | ActionStatus::Planned | ActionStatus::Cached
+    )
+  }
+}
+
+/// Итог выполнения Действия.
+#[derive(Clone, Debug)]
+pub(crate) struct ActionOutcome {
+  pub(crate) title: String,
+  /// Короткое имя и версия Действия.
+  pub(crate) info: String,
+  /// Тип Действия.
+  pub(crate) kind: &'static str,
+  pub(crate) status: ActionStatus,
+  /// Время начала выполнения.
+  pub(crate) started: DateTime<Local>,
+  pub(crate) elapsed: Duration,
+  /// Коды завершения выполненных команд.
+  pub(crate) exit_codes: Vec<Option<i32>>,
+}
+
+impl ActionOutcome {
+  fn new(action: &DescribedAction, status: ActionStatus, started: DateTime<Local>, elapsed: Duration, exit_codes: Vec<Option<i32>>) -> Self {
+    Self {
+      title: action.title.to_owned(),
+      info: info2str_simple(&action.info),
+      kind: action.kind(),
+      status,
+      started,
+      elapsed,
+      exit_codes,
+    }
+  }
+}
+
+/// Итог выполнения Пайплайна.
+#[derive(Clone, Debug)]
+pub(crate) struct PipelineOutcome {
+  /// Идентификатор запуска в истории сборок.
+  pub(crate) run_id: String,
+  pub(crate) title: String,
+  /// Итоги выполненных Действий (в порядке выполнения).
+  pub(crate) actions: Vec<ActionOutcome>,
+  /// Статусы основных Действий в порядке их следования в Пайплайне; `None` - Действие не выполнялось.
+  pub(crate) action_statuses: Vec<Option<ActionStatus>>,
+  /// Размещённые артефакты.
+  pub(crate) artifacts: Vec<PathBuf>,
+  /// Время начала выполнения.
+  pub(crate) started: DateTime<Local>,
+  pub(crate) elapsed: Duration,
+  /// Индекс Действия, на котором Пайплайн был остановлен.
+  pub(crate) failed_action: Option<usize>,
+}
+
+impl PipelineOutcome {
+  pub(crate) fn success(&self) -> bool {
+    self.failed_action.is_none()
+  }
+  
+  pub(crate) fn aborted(&self) -> bool {
+    self.status() == ActionStatus::Aborted
+  }
+  
+  /// Статус Пайплайна: статус Действия, на котором он был остановлен, или `Done`.
+  pub(crate) fn status(&self) -> ActionStatus {
+    self.failed_action.and_then(|i| self.actions.get(i)).map_or(ActionStatus::Done, |a| a.status)
+  }
+}
+
+/// Выполняет Пайплайн.
+/// 
+/// Итог выполнения возвращается и при ошибке Деплойера, чтобы выполненная часть Пайплайна попала в итоговую таблицу,
+/// отчёты и состояние для продолжения сборки (`--resume`).
 pub(crate) fn execute_pipeline(
   config: &DeployerProjectOptions,
   env: BuildEnvironment,
   pipeline: &DescribedPipeline,
-) -> anyhow::Result<()> {
-  use std::io::{stdout, Write};
-  use std::time::Instant;
-  
-  let log_file = generate_build_log_filepath(
+  preset: &[Option<ActionStatus>],
+) -> (PipelineOutcome, anyhow::Result<()>) {
+  let run_id = history::new_run_id();
+  // При пробном запуске лог сборки не ведётся, а запуск не попадает в историю
+  let log_file = (!env.dry_run).then(|| generate_build_log_filepath(
     &config.project_name,
     &pipeline.title,
+    &run_id,
+    env.log_format,
     env.cache_dir,
-  );
+  ));
+  let log = log_file.as_deref().map(|path| BuildLog { path, format: env.log_format, run_id: &run_id });
+  let env = BuildEnvironment { log, default_timeout: pipeline.default_timeout, ..env };
   
-  if !env.silent_build { println!("{}", i18n::STARTING_PIPELINE.replace("{}", &pipeline.title)); }
-  build_log(&log_file, &[format!("Starting the `{}` Pipeline...", pipeline.title)])?;
+  let pipeline_start = Instant::now();
+  let mut outcome = PipelineOutcome {
+    run_id: run_id.to_owned(),
+    title: pipeline.title.to_owned(),
+    actions: vec![],
+    action_statuses: vec![None; pipeline.actions.len()],
+    artifacts: vec![],
+    started: Local::now(),
+    elapsed: Duration::ZERO,
+    failed_action: None,
+  };
   
-  let mut cntr = 1usize;
+  if !env.silent_build { env.println(i18n::STARTING_PIPELINE.replace("{}", &pipeline.title)); }
+  if let Some(log) = env.log {
+    let res = log.event(
+      LogEvent::PipelineStart { project: &config.project_name, pipeline: &pipeline.title, commit: env.commit },
+      &[format!("Starting the `{}` Pipeline (run `{}`)...", pipeline.title, run_id)],
+    );
+    let res = res.and_then(|_| match env.commit {
+      Some(commit) => log.text(&[format!("{}: {}", i18n::BUILD_COMMIT, commit)]),
+      None => Ok(()),
+    });
+    if res.is_err() { return (outcome, res) }
+    
+    history::record_start(env.cache_dir, RunRecord {
+      id: run_id.to_owned(),
+      project: config.project_name.to_owned(),
+      pipeline: pipeline.title.to_owned(),
+      started: Local::now(),
+      duration: None,
+      status: None,
+      build_folder: env.build_dir.canonicalize().unwrap_or_else(|_| env.build_dir.to_path_buf()),
+      commit: env.commit.map(|c| c.to_owned()),
+      log: log.path.to_path_buf(),
+      pid: Some(std::process::id()),
+    });
+  }
+  
+  let cache_keys = if env.dry_run { Ok(vec![]) } else { restore_caches(&pipeline.caches, &config.project_name, env) };
+  let mut res = match &cache_keys {
+    Ok(_) => execute_actions(config, env, pipeline, preset, &mut outcome),
+    Err(_) => Ok(()),
+  };
+  match cache_keys {
+    Ok(cache_keys) if res.is_ok() && outcome.success() && !cache_keys.is_empty() => {
+      res = save_caches(&pipeline.caches, &config.project_name, env, &cache_keys);
+    },
+    Err(e) => res = Err(e),
+    _ => {},
+  }
+  outcome.elapsed = pipeline_start.elapsed();
+  
+  if let Some(log) = env.log {
+    let status = if res.is_ok() { outcome.status() } else { ActionStatus::Failed };
+    let logged = log.event(LogEvent::PipelineEnd { pipeline: &pipeline.title, status, duration: outcome.elapsed.as_secs_f64() }, &[]);
+    history::record_finish(env.cache_dir, &run_id, status, outcome.elapsed);
+    if res.is_ok() { res = logged; }
+  }
+  
+  (outcome, res)
+}
+
+/// Выполняет основные Действия Пайплайна, а затем - Действия `on_failure` и `always`.
+/// 
+/// Действия `on_failure` и `always` выполняются и при ошибке Деплойера во время выполнения основных Действий.
+fn execute_actions(
+  config: &DeployerProjectOptions,
+  env: BuildEnvironment,
+  pipeline: &DescribedPipeline,
+  preset: &[Option<ActionStatus>],
+  outcome: &mut PipelineOutcome,
+) -> anyhow::Result<()> {
+  let mut res = if pipeline.actions.iter().any(|a| a.needs.is_some()) {
+    execute_actions_graph(config, env, pipeline, preset, outcome)
+  } else {
+    execute_actions_sequence(config, env, pipeline, preset, outcome)
+  };
+  
+  if res.is_ok() && outcome.success() && let Some(log) = env.log {
+    res = env.build_dir.canonicalize().map_err(anyhow::Error::from).and_then(|canonicalized| {
+      let canonicalized = canonicalized.to_str().expect("Can't convert `Path` to string!");
+      if !env.silent_build { env.println(format!("{}: {}", i18n::BUILD_PATH, canonicalized)); }
+      log.message(&[format!("{}: {}", i18n::BUILD_PATH, canonicalized)])
+    });
+  }
+  
+  let hooks_res = execute_hooks(config, env, pipeline, outcome, res.is_err());
+  join_errors(res.err().into_iter().chain(hooks_res.err()).collect())
+}
+
+/// Выполняет основные Действия Пайплайна по порядку до первого неудачно выполненного.
+fn execute_actions_sequence(
+  config: &DeployerProjectOptions,
+  env: BuildEnvironment,
+  pipeline: &DescribedPipeline,
+  preset: &[Option<ActionStatus>],
+  outcome: &mut PipelineOutcome,
+) -> anyhow::Result<()> {
   let total = pipeline.actions.len();
-  for action in &pipeline.actions {
-    if !env.silent_build {
-      if !env.no_pipe {
-        print!("[{}/{}] {} `{}`...", cntr, total, i18n::STARTING_ACTION, action.title.blue().italic());
-      } else {
-        println!("[{}/{}] {} `{}`...", cntr, total, i18n::STARTING_ACTION, action.title.blue().italic());
-      }
-      build_log(&log_file, &[format!("[{}/{}] {} `{}`...", cntr, total, i18n::STARTING_ACTION, action.title)])?;
-    }
-    stdout().flush()?;
-    let now = Instant::now();
-    
-    let (status, output) = match &action.action {
-      Action::Custom(cmd) => cmd.execute(env)?,
-      Action::Check(check) => check.execute(env)?,
-      Action::PreBuild(a) | Action::Build(a) | Action::PostBuild(a) | Action::Test(a) => a.execute(env)?,
-      Action::ProjectClean(pc_action) => pc_action.execute(env)?,
-      Action::Pack(a) | Action::Deliver(a) | Action::Install(a) => a.execute(env)?,
-      Action::ConfigureDeploy(a) | Action::Deploy(a) | Action::PostDeploy(a) => a.execute(env)?,
-      Action::Observe(o_action) => o_action.execute(env)?,
-      Action::ForceArtifactsEnplace => {
-        enplace_artifacts(config, env, false)?;
-        
-        let mut modified_env = env;
-        let artifacts_dir = modified_env.build_dir.to_path_buf().join(ARTIFACTS_DIR);
-        modified_env.artifacts_dir = &artifacts_dir;
-        enplace_artifacts(config, modified_env, false)?;
-        
-        (true, vec![i18n::ARTIFACTS_ENPLACED.into()])
-      },
-      Action::Interrupt => {
-        println!();
-        inquire::Confirm::new(i18n::INTERRUPT).with_default(true).prompt()?;
-        (true, vec![])
-      },
-      
+  for (i, action) in pipeline.actions.iter().enumerate() {
+    let (action_outcome, error) = match preset.get(i).copied().flatten() {
+      Some(status) => (skip_action(env, action, status, i + 1, total)?, None),
+      None => try_execute_action(config, env, action, i + 1, total),
     };
+    let status = action_outcome.status;
+    outcome.action_statuses[i] = Some(status);
+    outcome.actions.push(action_outcome);
     
-    let status_str = match status {
-      true => i18n::DONE.to_string(),
-      false => i18n::GOT_ERROR.red().bold().to_string(),
-    };
+    if !status.is_ok() {
+      outcome.failed_action = Some(outcome.actions.len() - 1);
+      if let Some(e) = error { return Err(e) }
+      break
+    }
+  }
+  
+  Ok(())
+}
+
+/// Объединяет ошибки выполнения в одну.
+pub(crate) fn join_errors(mut errors: Vec<anyhow::Error>) -> anyhow::Result<()> {
+  match errors.len() {
+    0 => Ok(()),
+    1 => Err(errors.remove(0)),
+    _ => Err(anyhow::anyhow!("{}", errors.iter().map(|e| format!("{:#}", e)).collect::<Vec<_>>().join("\n"))),
+  }
+}
+
+/// Плейсхолдер заголовка Действия, на котором Пайплайн был остановлен (для Действий `on_failure` и `always`).
+const FAILED_ACTION_PLACEHOLDER: &str = "<failed-action>";
+/// Плейсхолдер статуса Действия, на котором Пайплайн был остановлен (для Действий `on_failure` и `always`).
+const FAILED_STATUS_PLACEHOLDER: &str = "<failed-status>";
+
+/// Выполняет Действия `on_failure` (если Пайплайн выполнен неудачно или с ошибкой - `errored`) и `always`.
+/// 
+/// Действия выполняются по порядку, даже если какое-то из них завершилось с ошибкой (в том числе с ошибкой Деплойера -
+/// такие ошибки возвращаются вместе после выполнения всех Действий). При прерывании сборки пользователем (`Ctrl-C`)
+/// не выполняются.
+fn execute_hooks(
+  config: &DeployerProjectOptions,
+  env: BuildEnvironment,
+  pipeline: &DescribedPipeline,
+  outcome: &mut PipelineOutcome,
+  errored: bool,
+) -> anyhow::Result<()> {
+  if interrupt::interrupted() { return Ok(()) }
+  
+  let failed = outcome.failed_action.and_then(|i| outcome.actions.get(i));
+  let placeholders = [
+    (FAILED_ACTION_PLACEHOLDER, failed.map(|a| a.title.to_owned()).unwrap_or_default()),
+    (FAILED_STATUS_PLACEHOLDER, failed.map(|a| format!("{:?}", a.status)).unwrap_or_default()),
+  ];
+  let env = BuildEnvironment { injected_placeholders: &placeholders, ..env };
+  
+  let hooks = [
+    // При пробном запуске выводятся и Действия `on_failure`
+    (i18n::RUNNING_ON_FAILURE_ACTIONS, "on_failure", if outcome.success() && !errored && !env.dry_run { &[][..] } else { &pipeline.on_failure[..] }),
+    (i18n::RUNNING_ALWAYS_ACTIONS, "always", &pipeline.always[..]),
+  ];
+  let mut errors = vec![];
+  'hooks: for (message, list_name, actions) in hooks {
+    if actions.is_empty() { continue }
     
-    let elapsed = now.elapsed();
-    if !env.no_pipe { build_log(&log_file, &output)?; }
-    build_log(&log_file, &[
+    if !env.silent_build { env.println(message); }
+    if let Some(log) = env.log && let Err(e) = log.message(&[format!("Running `{}` Actions...", list_name)]) { errors.push(e); }
+    
+    let total = actions.len();
+    for (i, action) in actions.iter().enumerate() {
+      let (action_outcome, error) = try_execute_action(config, env, action, i + 1, total);
+      let status = action_outcome.status;
+      outcome.actions.push(action_outcome);
+      errors.extend(error);
+      
+      if !status.is_ok() && outcome.failed_action.is_none() { outcome.failed_action = Some(outcome.actions.len() - 1); }
+      if status == ActionStatus::Aborted || interrupt::interrupted() { break 'hooks }
+    }
+  }
+  
+  join_errors(errors)
+}
+
+/// Выполняет Действия Пайплайна в соответствии с их зависимостями (`needs`).
+/// 
+/// Независимые Действия выполняются параллельно; Действия, зависящие от неудачно выполненных, пропускаются,
+/// при этом не связанные с ними Действия продолжают выполняться.
+fn execute_actions_graph(
+  config: &DeployerProjectOptions,
+  env: BuildEnvironment,
+  pipeline: &DescribedPipeline,
+  preset: &[Option<ActionStatus>],
+  outcome: &mut PipelineOutcome,
+) -> anyhow::Result<()> {
+  let dependencies = pipeline.dependencies()?;
+  let total = pipeline.actions.len();
+  let mut statuses: Vec<Option<ActionStatus>> = vec![None; total];
+  let mut started = vec![false; total];
+  let mut running = 0usize;
+  let mut stop = false;
+  let mut error = None;
+  
+  for (i, status) in preset.iter().enumerate().take(total) {
+    let Some(status) = *status else { continue };
+    started[i] = true;
+    statuses[i] = Some(status);
+    outcome.actions.push(skip_action(env, &pipeline.actions[i], status, i + 1, total)?);
+  }
+  
+  std::thread::scope(|s| {
+    let (tx, rx) = std::sync::mpsc::channel();
+    
+    loop {
+      // Действия, зависящие от неудачно выполненных, пропускаются
+      let mut skipped = true;
+      while skipped && !stop {
+        skipped = false;
+        for i in 0..total {
+          if started[i] || !dependencies[i].iter().any(|d| statuses[*d].is_some_and(|s| !s.is_ok())) { continue }
+          
+          started[i] = true;
+          statuses[i] = Some(ActionStatus::Skipped);
+          skipped = true;
+          
+          match skip_action(env, &pipeline.actions[i], ActionStatus::Skipped, i + 1, total) {
+            Ok(action_outcome) => outcome.actions.push(action_outcome),
+            Err(e) => {
+              error = Some(e);
+              stop = true;
+            },
+          }
+        }
+      }
+      
+      if !stop && !interrupt::interrupted() {
+        for i in 0..total {
+          if started[i] || !dependencies[i].iter().all(|d| statuses[*d].is_some_and(|s| s.is_ok())) { continue }
+          
+          started[i] = true;
+          running += 1;
+          let action = &pipeline.actions[i];
+          let tx = tx.clone();
+          s.spawn(move || { let _ = tx.send((i, try_execute_action(config, env, action, i + 1, total))); });
+        }
+      }
+      
+      if running == 0 { break }
+      let Ok((i, (action_outcome, action_error))) = rx.recv() else { break };
+      running -= 1;
+      
+      statuses[i] = Some(action_outcome.status);
+      if action_outcome.status == ActionStatus::Aborted { stop = true; }
+      if !action_outcome.status.is_ok() && outcome.failed_action.is_none() {
+        outcome.failed_action = Some(outcome.actions.len());
+      }
+      outcome.actions.push(action_outcome);
+      
+      if let Some(e) = action_error {
+        if error.is_none() { error = Some(e); }
+        stop = true;
+      }
+    }
+  });
+  
+  outcome.action_statuses = statuses;
+  
+  match error {
+    Some(e) => Err(e),
+    None => Ok(()),
+  }
+}
+
+/// Выполняет Действие Пайплайна; при ошибке Деплойера Действие считается выполненным неудачно, а ошибка
+/// возвращается вместе с его итогом.
+fn try_execute_action(
+  config: &DeployerProjectOptions,
+  env: BuildEnvironment,
+  action: &DescribedAction,
+  number: usize,
+  total: usize,
+) -> (ActionOutcome, Option<anyhow::Error>) {
+  let started = Local::now();
+  let now = Instant::now();
+  
+  match execute_action(config, env, action, number, total) {
+    Ok(action_outcome) => (action_outcome, None),
+    Err(e) => {
+      let action_outcome = ActionOutcome::new(action, ActionStatus::Failed, started, now.elapsed(), vec![]);
+      // Ошибка записи итога в лог не должна скрыть исходную ошибку
+      let _ = report_action(env, &action_outcome, number, total, &[]);
+      (action_outcome, Some(e))
+    },
+  }
+}
+
+/// Выполняет Действие Пайплайна, выводит и записывает в лог результат выполнения.
+fn execute_action(
+  config: &DeployerProjectOptions,
+  env: BuildEnvironment,
+  action: &DescribedAction,
+  number: usize,
+  total: usize,
+) -> anyhow::Result<ActionOutcome> {
+  if !env.silent_build {
+    env.println(format!("[{}/{}] {} `{}`...", number, total, i18n::STARTING_ACTION, action.title.blue().italic()));
+  }
+  if let Some(log) = env.log {
+    log.event(
+      LogEvent::ActionStart { action: &action.title, number, total },
+      &[format!("[{}/{}] {} `{}`...", number, total, i18n::STARTING_ACTION, action.title)],
+    )?;
+  }
+  let started = Local::now();
+  let now = Instant::now();
+  let exit_codes = ExitCodes::default();
+  let env = BuildEnvironment { exit_codes: &exit_codes, ..env };
+  
+  if env.dry_run && let Some(when) = &action.when {
+    if !env.silent_build { env.println(i18n::DRY_RUN_CONDITION.replace("{}", when)); }
+  } else if let Some(when) = &action.when && !interrupt::interrupted() && !Condition::parse(when)?.evaluate(config, env)? {
+    let action_outcome = ActionOutcome::new(action, ActionStatus::ConditionNotMet, started, now.elapsed(), vec![]);
+    report_action(env, &action_outcome, number, total, &[i18n::CONDITION_IS_FALSE.replace("{}", when)])?;
+    return Ok(action_outcome)
+  }
+  
+  // Действие с входными файлами кэшируется: при совпадении ключа выходные файлы восстанавливаются из кэша
+  let cache_key = match &action.inputs {
+    Some(inputs) if !env.dry_run && !interrupt::interrupted() => Some(action_cache::cache_key(env, action, inputs)?),
+    _ => None,
+  };
+  if let Some(key) = &cache_key && action_cache::restore(env, action, key)? {
+    let action_outcome = ActionOutcome::new(action, ActionStatus::Cached, started, now.elapsed(), vec![]);
+    report_action(env, &action_outcome, number, total, &[i18n::ACTION_CACHE_HIT.replace("{}", &key[..12])])?;
+    return Ok(action_outcome)
+  }
+  
+  let mut aborted = false;
+  
+  let res = match &action.action {
+    _ if interrupt::interrupted() => {
+      aborted = true;
+      Ok((false, vec![]))
+    },
+    Action::Custom(cmd) => cmd.execute(env),
+    Action::Check(check) => check.execute(env),
+    Action::PreBuild(a) | Action::Build(a) | Action::PostBuild(a) | Action::Test(a) => a.execute(env),
+    Action::ProjectClean(pc_action) => pc_action.execute(env),
+    Action::Pack(a) | Action::Deliver(a) | Action::Install(a) => a.execute(env),
+    Action::ConfigureDeploy(a) | Action::Deploy(a) | Action::PostDeploy(a) => a.execute(env),
+    Action::Observe(o_action) => o_action.execute(env),
+    Action::ForceArtifactsEnplace => {
+      enplace_artifacts(config, env, false)?;
+      
+      let mut modified_env = env;
+      let artifacts_dir = modified_env.build_dir.to_path_buf().join(ARTIFACTS_DIR);
+      modified_env.artifacts_dir = &artifacts_dir;
+      enplace_artifacts(config, modified_env, false)?;
+      
+      Ok((true, if env.dry_run { vec![] } else { vec![i18n::ARTIFACTS_ENPLACED.into()] }))
+    },
+    Action::Interrupt if env.dry_run => Ok((true, vec![i18n::DRY_RUN_INTERRUPT.to_string()])),
+    Action::Interrupt => {
+      use inquire::InquireError;
+      
+      println!();
+      match inquire::Confirm::new(i18n::INTERRUPT).with_default(true).prompt() {
+        Ok(true) => Ok((true, vec![])),
+        Ok(false) | Err(InquireError::OperationCanceled | InquireError::OperationInterrupted) => {
+          aborted = true;
+          Ok((false, vec![]))
+        },
+        Err(e) => return Err(e.into()),
+      }
+    },
+  
+  };
+  
+  let mut timed_out = false;
+  let (status, mut output) = match res {
+    Err(e) if e.is::<CommandTimedOut>() => {
+      timed_out = true;
+      (false, vec![])
+    },
+    res => res?,
+  };
+  // Действие, во время которого выполнение было прервано, считается прерванным
+  aborted = aborted || interrupt::interrupted();
+  
+  let action_status = match (status, aborted, timed_out) {
+    (_, true, _) => ActionStatus::Aborted,
+    (_, _, true) => ActionStatus::TimedOut,
+    (true, _, _) if env.dry_run => ActionStatus::Planned,
+    (true, _, _) => ActionStatus::Done,
+    (false, _, _) => ActionStatus::Failed,
+  };
+  
+  if action_status == ActionStatus::Done && let Some(key) = &cache_key && !action_cache::store(env, action, key)? {
+    output.push(i18n::ACTION_CACHE_NO_OUTPUTS.to_string());
+  }
+  
+  let action_outcome = ActionOutcome::new(action, action_status, started, now.elapsed(), exit_codes.take());
+  report_action(env, &action_outcome, number, total, &output)?;
+  
+  Ok(action_outcome)
+}
+
+/// Отмечает Действие как невыполненное с указанным статусом.
+fn skip_action(
+  env: BuildEnvironment,
+  action: &DescribedAction,
+  status: ActionStatus,
+  number: usize,
+  total: usize,
+) -> anyhow::Result<ActionOutcome> {
+  let action_outcome = ActionOutcome::new(action, status, Local::now(), Duration::ZERO, vec![]);
+  report_action(env, &action_outcome, number, total, &[])?;
+  Ok(action_outcome)
+}
+
+/// Выводит и записывает в лог вывод и статус выполнения Действия.
+fn report_action(
+  env: BuildEnvironment,
+  action_outcome: &ActionOutcome,
+  number: usize,
+  total: usize,
+  output: &[String],
+) -> anyhow::Result<()> {
+  if let Some(log) = env.log {
+    log.message(output)?;
+    log.event(LogEvent::ActionEnd {
+      action: &action_outcome.title,
+      status: action_outcome.status,
+      duration: action_outcome.elapsed.as_secs_f64(),
+    }, &[
       format!(
-        "[{}/{}] {} -{} ({:.2?}).",
-        cntr,
+        "[{}/{}] {} `{}` -{} ({:.2?}).",
+        number,
         total,
-        i18n::STARTING_ACTION.replace("{}", &action.title),
-        if status { i18n::DONE } else { i18n::GOT_ERROR },
-        elapsed,
+        i18n::STARTING_ACTION,
+        action_outcome.title,
+        action_outcome.status.describe(),
+        action_outcome.elapsed,
       ),
     ])?;
+  }
+  
+  if !env.silent_build {
+    let status_str = match action_outcome.status {
+      ActionStatus::Done | ActionStatus::Cached => action_outcome.status.describe().to_string(),
+      ActionStatus::Skipped | ActionStatus::ConditionNotMet | ActionStatus::PreviouslyDone | ActionStatus::NotSelected | ActionStatus::Planned => {
+        action_outcome.status.describe().yellow().to_string()
+      },
+      _ => action_outcome.status.describe().red().bold().to_string(),
+    };
     
-    if !env.silent_build {
-      if !env.no_pipe {
-        println!("{} ({}).", status_str, format!("{:.2?}", elapsed).green());
-        for line in output { println!("{}", line); }
-      } else {
-        println!("[{}/{}] {} -{} ({}).", cntr, total, i18n::STARTING_ACTION.replace("{}", &action.title.blue().italic()), status_str, format!("{:.2?}", elapsed).green());
-      }
-    }
-    
-    cntr += 1;
+    for line in output { env.println(line); }
+    env.println(format!(
+      "[{}/{}] {} `{}` -{} ({}).",
+      number,
+      total,
+      i18n::STARTING_ACTION,
+      action_outcome.title.blue().italic(),
+      status_str,
+      format!("{:.2?}", action_outcome.elapsed).green(),
+    ));
+  }
+  
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  
+  /// Пайплайн из Действий с указанными заголовками и зависимостями (`None` - зависимости не указаны).
+  fn pipeline(actions: &[(&str, Option<&[&str]>)]) -> DescribedPipeline {
+    let actions = actions
+      .iter()
+      .map(|(title, needs)| {
+        let mut action = serde_json::json!({
+          "title": title,
+          "desc": "",
+          "info": "test@0.1.0",
+          "tags": [],
+          "action": { "Custom": { "bash_c": "true", "ignore_fails": false, "show_success_output": false, "show_bash_c": false } },
+        });
+        if let Some(needs) = needs { action["needs"] = serde_json::json!(needs); }
+        action
+      })
+      .collect::<Vec<_>>();
     
-    if !status { return Ok(()) }
+    serde_json::from_value(serde_json::json!({
+      "title": "test",
+      "desc": "",
+      "info": "test@0.1.0",
+      "tags": [],
+      "actions": actions,
+    })).unwrap()
   }
   
-  let canonicalized = env.build_dir.canonicalize()?;
-  let canonicalized = canonicalized.to_str().expect("Can't convert `Path` to string!");
-  if !env.silent_build { println!("{}: {}", i18n::BUILD_PATH, canonicalized); }
-  build_log(&log_file, &[format!("{}: {}", i18n::BUILD_PATH, canonicalized)])?;
+  #[test]
+  fn actions_without_needs_depend_on_previous() {
+    let deps = pipeline(&[("a", None), ("b", None), ("c", Some(&[]))]).dependencies().unwrap();
+    assert_eq!(deps, vec![vec![], vec![0], vec![]]);
+  }
   
-  Ok(())
+  #[test]
+  fn actions_needs() {
+    let deps = pipeline(&[("a", Some(&[])), ("b", Some(&[])), ("c", Some(&["a", "b"])), ("d", Some(&["c"]))]).dependencies().unwrap();
+    assert_eq!(deps, vec![vec![], vec![], vec![0, 1], vec![2]]);
+  }
+  
+  #[test]
+  fn unknown_and_ambiguous_needs() {
+    assert!(pipeline(&[("a", Some(&["b"]))]).dependencies().is_err());
+    assert!(pipeline(&[("a", Some(&[])), ("a", Some(&[])), ("b", Some(&["a"]))]).dependencies().is_err());
+  }
+  
+  #[test]
+  fn cyclic_needs() {
+    let err = pipeline(&[("a", Some(&["a"]))]).dependencies().unwrap_err().to_string();
+    assert!(err.contains("`a`"), "{}", err);
+    
+    // В сообщение попадают только Действия, входящие в цикл
+    let err = pipeline(&[("a", Some(&[])), ("b", Some(&["a", "c"])), ("c", Some(&["b"])), ("d", Some(&["c"]))])
+      .dependencies()
+      .unwrap_err()
+      .to_string();
+    assert!(err.contains("`b`, `c`"), "{}", err);
+    assert!(!err.contains("`a`") && !err.contains("`d`"), "{}", err);
+  }
 }
@@ -77,7 +77,7 @@ impl DeployerProjectOptions {
         i18n::EDIT_PROJECT_PIPELINES => self.pipelines.edit_from_prompt(globals)?,
         i18n::EDIT_PROJECT_REASSIGN => for pipeline in &mut self.pipelines {
           for action in &mut pipeline.actions {
-            *action = action.prompt_setup_for_project(&self.langs, &self.deploy_toolkit, &self.targets, &self.variables, &self.artifacts)?;
+            *action = action.prompt_setup_for_project(&self.langs, &self.deploy_toolkit, &self.targets, &self.variables, &self.artifacts, None)?;
           }
         },
         _ => {},
@@ -0,0 +1,246 @@
+use chrono::{DateTime, Local};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::cmd::BuildArgs;
+use crate::configs::DeployerProjectOptions;
+use crate::pipelines::{ActionOutcome, ActionStatus, PipelineOutcome};
+
+/// Отчёт о сборке (`--report`).
+#[derive(Serialize)]
+struct BuildReport<'a> {
+  project: &'a str,
+  pipelines: Vec<PipelineReport<'a>>,
+}
+
+/// Отчёт о выполнении Пайплайна.
+#[derive(Serialize)]
+struct PipelineReport<'a> {
+  /// Идентификатор запуска в истории сборок.
+  run_id: &'a str,
+  title: &'a str,
+  status: ActionStatus,
+  /// Действие, на котором Пайплайн был остановлен.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  failed_action: Option<&'a str>,
+  started: String,
+  finished: String,
+  /// Длительность выполнения в секундах.
+  duration: f64,
+  actions: Vec<ActionReport<'a>>,
+  artifacts: Vec<ArtifactReport>,
+}
+
+/// Отчёт о выполнении Действия.
+#[derive(Serialize)]
+struct ActionReport<'a> {
+  title: &'a str,
+  info: &'a str,
+  #[serde(rename = "type")]
+  kind: &'a str,
+  status: ActionStatus,
+  started: String,
+  finished: String,
+  /// Длительность выполнения в секундах.
+  duration: f64,
+  /// Коды завершения выполненных команд; `null` - процесс завершён сигналом.
+  exit_codes: &'a [Option<i32>],
+}
+
+/// Файл артефакта и его хэш.
+#[derive(Serialize)]
+struct ArtifactReport {
+  path: PathBuf,
+  sha256: String,
+}
+
+/// Записывает отчёты о сборке в форматах JSON (`--report`) и JUnit XML (`--junit`).
+pub(crate) fn write_reports(
+  config: &DeployerProjectOptions,
+  outcomes: &[PipelineOutcome],
+  args: &BuildArgs,
+) -> anyhow::Result<()> {
+  if let Some(path) = &args.report {
+    let report = BuildReport {
+      project: &config.project_name,
+      pipelines: outcomes.iter().map(pipeline_report).collect::<anyhow::Result<_>>()?,
+    };
+    std::fs::write(path, serde_json::to_string_pretty(&report)?)
+      .map_err(|e| anyhow::anyhow!("Can't write the build report to `{}` due to: {}", path.display(), e))?;
+  }
+  
+  if let Some(path) = &args.junit {
+    std::fs::write(path, junit_report(config, outcomes))
+      .map_err(|e| anyhow::anyhow!("Can't write the JUnit report to `{}` due to: {}", path.display(), e))?;
+  }
+  
+  Ok(())
+}
+
+fn pipeline_report(outcome: &PipelineOutcome) -> anyhow::Result<PipelineReport<'_>> {
+  let mut artifacts = vec![];
+  for path in &outcome.artifacts { hash_artifact(path, &mut artifacts)?; }
+  
+  Ok(PipelineReport {
+    run_id: &outcome.run_id,
+    title: &outcome.title,
+    status: outcome.status(),
+    failed_action: outcome.failed_action.and_then(|i| outcome.actions.get(i)).map(|a| a.title.as_str()),
+    started: outcome.started.to_rfc3339(),
+    finished: finished_at(outcome.started, outcome.elapsed).to_rfc3339(),
+    duration: outcome.elapsed.as_secs_f64(),
+    actions: outcome.actions.iter().map(action_report).collect(),
+    artifacts,
+  })
+}
+
+fn action_report(outcome: &ActionOutcome) -> ActionReport<'_> {
+  ActionReport {
+    title: &outcome.title,
+    info: &outcome.info,
+    kind: outcome.kind,
+    status: outcome.status,
+    started: outcome.started.to_rfc3339(),
+    finished: finished_at(outcome.started, outcome.elapsed).to_rfc3339(),
+    duration: outcome.elapsed.as_secs_f64(),
+    exit_codes: &outcome.exit_codes,
+  }
+}
+
+fn finished_at(started: DateTime<Local>, elapsed: Duration) -> DateTime<Local> {
+  started + chrono::Duration::from_std(elapsed).unwrap_or_default()
+}
+
+/// Вычисляет хэши файла артефакта или всех файлов папки артефакта.
+fn hash_artifact(path: &Path, artifacts: &mut Vec<ArtifactReport>) -> anyhow::Result<()> {
+  if path.is_dir() {
+    let mut entries = std::fs::read_dir(path)?.map(|e| e.map(|e| e.path())).collect::<Result<Vec<_>, _>>()?;
+    entries.sort();
+    for entry in entries { hash_artifact(&entry, artifacts)?; }
+    return Ok(())
+  }
+  
+  let mut hasher = Sha256::new();
+  let mut file = std::fs::File::open(path).map_err(|e| anyhow::anyhow!("Can't read the `{}` artifact due to: {}", path.display(), e))?;
+  std::io::copy(&mut file, &mut hasher)?;
+  artifacts.push(ArtifactReport { path: path.to_path_buf(), sha256: format!("{:x}", hasher.finalize()) });
+  
+  Ok(())
+}
+
+/// Формирует отчёт в формате JUnit XML: Пайплайны - наборы тестов, Действия - тесты.
+fn junit_report(config: &DeployerProjectOptions, outcomes: &[PipelineOutcome]) -> String {
+  let count = |outcome: &PipelineOutcome, failed: bool| outcome.actions.iter().filter(|a| junit_failed(a.status) == Some(failed)).count();
+  
+  let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+  xml.push_str(&format!(
+    "<testsuites name=\"{}\" tests=\"{}\" failures=\"{}\" time=\"{:.3}\">\n",
+    escape_xml(&config.project_name),
+    outcomes.iter().map(|o| o.actions.len()).sum::<usize>(),
+    outcomes.iter().map(|o| count(o, true)).sum::<usize>(),
+    outcomes.iter().map(|o| o.elapsed.as_secs_f64()).sum::<f64>(),
+  ));
+  
+  for outcome in outcomes {
+    xml.push_str(&format!(
+      "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"0\" skipped=\"{}\" time=\"{:.3}\" timestamp=\"{}\">\n",
+      escape_xml(&outcome.title),
+      outcome.actions.len(),
+      count(outcome, true),
+      outcome.actions.len() - count(outcome, true) - count(outcome, false),
+      outcome.elapsed.as_secs_f64(),
+      outcome.started.format("%Y-%m-%dT%H:%M:%S"),
+    ));
+    
+    for action in &outcome.actions {
+      let testcase = format!(
+        "    <testcase name=\"{}\" classname=\"{}.{}\" time=\"{:.3}\"",
+        escape_xml(&action.title),
+        escape_xml(&outcome.title),
+        escape_xml(&action.info),
+        action.elapsed.as_secs_f64(),
+      );
+      let status = format!("{:?}", action.status);
+      match junit_failed(action.status) {
+        Some(false) => xml.push_str(&format!("{}/>\n", testcase)),
+        Some(true) => xml.push_str(&format!(
+          "{}>\n      <failure type=\"{}\" message=\"{}\">exit codes: {}</failure>\n    </testcase>\n",
+          testcase,
+          status,
+          escape_xml(action.status.describe().trim()),
+          action.exit_codes.iter().map(|c| c.map_or("signal".to_string(), |c| c.to_string())).collect::<Vec<_>>().join(", "),
+        )),
+        None => xml.push_str(&format!("{}>\n      <skipped message=\"{}\"/>\n    </testcase>\n", testcase, status)),
+      }
+    }
+    
+    xml.push_str("  </testsuite>\n");
+  }
+  
+  xml.push_str("</testsuites>\n");
+  xml
+}
+
+/// Результат Действия как теста JUnit: `Some(false)` - успех, `Some(true)` - ошибка, `None` - тест пропущен.
+fn junit_failed(status: ActionStatus) -> Option<bool> {
+  match status {
+    ActionStatus::Done | ActionStatus::Cached => Some(false),
+    ActionStatus::Failed | ActionStatus::Aborted | ActionStatus::TimedOut => Some(true),
+    ActionStatus::Skipped | ActionStatus::ConditionNotMet | ActionStatus::PreviouslyDone | ActionStatus::NotSelected | ActionStatus::Planned => None,
+  }
+}
+
+fn escape_xml(s: &str) -> String {
+  s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;").replace('\'', "&apos;")
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  
+  fn action(title: &str, status: ActionStatus) -> ActionOutcome {
+    ActionOutcome {
+      title: title.to_owned(),
+      info: "test@0.1.0".to_owned(),
+      kind: "Custom",
+      status,
+      started: Local::now(),
+      elapsed: Duration::from_millis(1500),
+      exit_codes: vec![Some(1), None],
+    }
+  }
+  
+  #[test]
+  fn xml_escaping() {
+    assert_eq!(escape_xml(r#"<a href="x">Tom & 'Jerry'</a>"#), "&lt;a href=&quot;x&quot;&gt;Tom &amp; &apos;Jerry&apos;&lt;/a&gt;");
+    // Уже экранированный текст экранируется повторно
+    assert_eq!(escape_xml("&amp;"), "&amp;amp;");
+    assert_eq!(escape_xml("plain text"), "plain text");
+  }
+  
+  #[test]
+  fn junit_report_is_escaped() {
+    let config = DeployerProjectOptions { project_name: "<project>".to_owned(), ..Default::default() };
+    let outcome = PipelineOutcome {
+      run_id: "1f2e3d4c".to_owned(),
+      title: "build & \"test\"".to_owned(),
+      actions: vec![action("Build <release>", ActionStatus::Done), action("Test 'all'", ActionStatus::Failed), action("Deploy", ActionStatus::Skipped)],
+      action_statuses: vec![],
+      artifacts: vec![],
+      started: Local::now(),
+      elapsed: Duration::from_secs(3),
+      failed_action: Some(1),
+    };
+    
+    let xml = junit_report(&config, &[outcome]);
+    assert!(xml.contains(r#"<testsuites name="&lt;project&gt;" tests="3" failures="1" time="3.000">"#), "{}", xml);
+    assert!(xml.contains(r#"<testsuite name="build &amp; &quot;test&quot;" tests="3" failures="1" errors="0" skipped="1""#), "{}", xml);
+    assert!(xml.contains(r#"<testcase name="Build &lt;release&gt;" classname="build &amp; &quot;test&quot;.test@0.1.0" time="1.500"/>"#), "{}", xml);
+    assert!(xml.contains(r#"<testcase name="Test &apos;all&apos;""#), "{}", xml);
+    assert!(xml.contains(r#"<failure type="Failed""#) && xml.contains("exit codes: 1, signal</failure>"), "{}", xml);
+    assert!(xml.contains(r#"<skipped message="Skipped"/>"#), "{}", xml);
+    assert!(!xml.contains("<project>") && !xml.contains("<release>"), "{}", xml);
+  }
+}
@@ -1,12 +1,16 @@
-use serde::{de::DeserializeOwned, Serialize};
+use clap::ValueEnum;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use ignore::Match;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::collections::BTreeSet;
 use std::fs::File;
 use std::io::{BufReader, BufWriter};
 use std::sync::OnceLock;
 use std::path::{Path, PathBuf};
 use crate::{CACHE_DIR, LOGS_DIR, PROJECT_CONF};
+use crate::pipelines::ActionStatus;
 
 pub(crate) static VERBOSE: OnceLock<bool> = OnceLock::new();
-const LOG_FILE_DELIMETER: &str = "================================================================";
 
 pub(crate) fn read<T: DeserializeOwned + Default>(folder: impl AsRef<Path>, file: impl AsRef<Path>) -> T {
   let mut path = PathBuf::new();
@@ -33,10 +37,17 @@ pub(crate) fn read_checked<T: DeserializeOwned>(filepath: impl AsRef<Path>) -> a
 }
 
 pub(crate) fn write<T: Serialize>(folder: impl AsRef<Path>, file: impl AsRef<Path>, config: &T) {
+  use std::io::Write;
+  
   let mut path = PathBuf::new();
   path.push(folder);
   path.push(file.as_ref());
-  let f = match File::create(path) {
+  // Файл записывается целиком во временный файл и затем подменяется, чтобы прерывание не оставило его повреждённым;
+  // у каждой записи свой временный файл, чтобы одновременные записи из разных процессов не портили друг друга
+  let mut tmp_path = path.clone().into_os_string();
+  tmp_path.push(format!(".{}.tmp", uuid::Uuid::new_v4().simple()));
+  let tmp_path = PathBuf::from(tmp_path);
+  let f = match File::create(&tmp_path) {
     Ok(file) => file,
     Err(_) => {
       log(format!("Can't save `{:?}` config file!", file.as_ref().as_os_str()));
@@ -44,17 +55,106 @@ pub(crate) fn write<T: Serialize>(folder: impl AsRef<Path>, file: impl AsRef<Pat
     }
   };
   
-  let writer = BufWriter::new(f);
+  let mut writer = BufWriter::new(f);
   
-  match serde_json::to_writer_pretty(writer, config) {
+  match serde_json::to_writer_pretty(&mut writer, config) {
     Ok(_) => (),
     Err(_) => {
       log(format!("Can't save `{:?}` config file due to serialization error!", file.as_ref().as_os_str()));
+      let _ = std::fs::remove_file(&tmp_path);
+      return
     },
   }
+  
+  if writer.flush().and_then(|_| std::fs::rename(&tmp_path, &path)).is_err() {
+    log(format!("Can't save `{:?}` config file!", file.as_ref().as_os_str()));
+    let _ = std::fs::remove_file(&tmp_path);
+  }
+}
+
+/// Межпроцессная блокировка (`flock`) файла блокировки; снимается при удалении.
+pub(crate) struct FileLock {
+  _file: File,
+}
+
+impl FileLock {
+  /// Дожидается исключительной блокировки, создавая файл блокировки при необходимости.
+  pub(crate) fn exclusive(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+    use std::os::fd::AsRawFd;
+    
+    let path = path.as_ref();
+    let file = File::options()
+      .create(true)
+      .truncate(false)
+      .write(true)
+      .open(path)
+      .map_err(|e| anyhow::anyhow!("Can't open `{}` lock file due to: {}", path.display(), e))?;
+    
+    // SAFETY: блокировка открытого файла; она снимается при закрытии файла.
+    while unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX) } != 0 {
+      let e = std::io::Error::last_os_error();
+      if e.kind() != std::io::ErrorKind::Interrupted {
+        return Err(anyhow::anyhow!("Can't lock `{}` file due to: {}", path.display(), e))
+      }
+    }
+    
+    Ok(Self { _file: file })
+  }
+}
+
+/// Файлы с правилами игнорирования, учитываемые при копировании проекта в папку сборки.
+pub(crate) const PROJECT_IGNORE_FILES: &[&str] = &[".gitignore", ".deployerignore"];
+/// Файлы с правилами игнорирования, учитываемые при размещении артефактов.
+pub(crate) const ARTIFACTS_IGNORE_FILES: &[&str] = &[".deployerignore"];
+
+/// Правила исключения файлов при копировании.
+/// 
+/// Помимо названий, исключаемых на любом уровне вложенности, учитываются правила в стиле `.gitignore` из файлов
+/// игнорирования каждой копируемой папки; правила вложенных папок имеют приоритет над правилами родительских.
+pub(crate) struct IgnoreRules<'a> {
+  names: &'a [&'a str],
+  files: &'static [&'static str],
+  stack: Vec<Gitignore>,
+}
+
+impl<'a> IgnoreRules<'a> {
+  pub(crate) fn new(names: &'a [&'a str], files: &'static [&'static str]) -> Self {
+    Self { names, files, stack: vec![] }
+  }
+  
+  /// Добавляет правила из файлов игнорирования папки.
+  pub(crate) fn enter(&mut self, dir: &Path) {
+    let mut builder = GitignoreBuilder::new(dir);
+    for file in self.files {
+      let path = dir.join(file);
+      if path.is_file() && let Some(e) = builder.add(&path) { log(format!("Invalid rules in `{}`: {}", path.display(), e)); }
+    }
+    self.stack.push(builder.build().unwrap_or_else(|_| Gitignore::empty()));
+  }
+  
+  /// Убирает правила папки, добавленные последними.
+  pub(crate) fn leave(&mut self) {
+    self.stack.pop();
+  }
+  
+  fn is_ignored(&self, path: &Path, is_dir: bool) -> bool {
+    let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+    // Конфигурация проекта нужна в папке сборки всегда
+    if name == PROJECT_CONF { return false }
+    if self.names.contains(&name) { return true }
+    
+    for rules in self.stack.iter().rev() {
+      match rules.matched(path, is_dir) {
+        Match::Ignore(_) => return true,
+        Match::Whitelist(_) => return false,
+        Match::None => {},
+      }
+    }
+    false
+  }
 }
 
-pub(crate) fn copy_all(src: impl AsRef<Path>, dst: impl AsRef<Path>, ignore: &[&str]) -> anyhow::Result<()> {
+pub(crate) fn copy_all(src: impl AsRef<Path>, dst: impl AsRef<Path>, ignore: &mut IgnoreRules) -> anyhow::Result<()> {
   if src.as_ref().is_file() {
     if let Some(parent) = dst.as_ref().parent() {
       std::fs::create_dir_all(parent)?;
@@ -63,17 +163,18 @@ pub(crate) fn copy_all(src: impl AsRef<Path>, dst: impl AsRef<Path>, ignore: &[&
     return Ok(())
   }
   std::fs::create_dir_all(&dst)?;
+  ignore.enter(src.as_ref());
   
   for entry in std::fs::read_dir(src)? {
     let entry = entry?;
     let name = entry.file_name();
     let name = name.to_str().unwrap_or("");
+    let ty = entry.file_type()?;
     
-    if ignore.contains(&name) { continue }
+    if ignore.is_ignored(&entry.path(), ty.is_dir()) { continue }
     
     log(format!("-> {}", name));
     
-    let ty = entry.file_type()?;
     let d = dst.as_ref().join(entry.file_name());
     if ty.is_dir() {
       copy_all(entry.path(), d, ignore)?;
@@ -87,9 +188,91 @@ pub(crate) fn copy_all(src: impl AsRef<Path>, dst: impl AsRef<Path>, ignore: &[&
     }
   }
   
+  ignore.leave();
+  Ok(())
+}
+
+/// Файл со списком путей, синхронизированных из папки проекта, в папке сборки.
+const SYNC_MANIFEST: &str = ".deploy-sync.json";
+
+/// Инкрементально синхронизирует папку проекта с папкой сборки.
+/// 
+/// Копируются только новые файлы и файлы, размер или время изменения которых отличаются; файлы, удалённые из проекта
+/// после прошлой синхронизации, удаляются из папки сборки. Файлы, созданные в папке сборки во время сборки, а также
+/// ранее скопированные файлы, которые теперь исключены правилами, не затрагиваются.
+pub(crate) fn sync_all(src: impl AsRef<Path>, dst: impl AsRef<Path>, ignore: &mut IgnoreRules) -> anyhow::Result<()> {
+  let (src, dst) = (src.as_ref(), dst.as_ref());
+  std::fs::create_dir_all(dst)?;
+  
+  let previous = read_checked::<BTreeSet<PathBuf>>(dst.join(SYNC_MANIFEST)).unwrap_or_default();
+  let mut synced = BTreeSet::new();
+  sync_dir(src, dst, Path::new(""), ignore, &mut synced)?;
+  
+  for path in previous.difference(&synced) {
+    if std::fs::symlink_metadata(src.join(path)).is_ok() { continue }
+    log(format!("x {}", path.display()));
+    remove_path(&dst.join(path))?;
+  }
+  
+  write(dst, SYNC_MANIFEST, &synced);
+  Ok(())
+}
+
+fn sync_dir(src: &Path, dst: &Path, rel: &Path, ignore: &mut IgnoreRules, synced: &mut BTreeSet<PathBuf>) -> anyhow::Result<()> {
+  ignore.enter(&src.join(rel));
+  
+  for entry in std::fs::read_dir(src.join(rel))? {
+    let entry = entry?;
+    let name = entry.file_name();
+    let ty = entry.file_type()?;
+    if name == SYNC_MANIFEST || ignore.is_ignored(&entry.path(), ty.is_dir()) { continue }
+    
+    let rel = rel.join(&name);
+    let (from, to) = (entry.path(), dst.join(&rel));
+    let existing = std::fs::symlink_metadata(&to).ok();
+    
+    if ty.is_dir() {
+      if existing.as_ref().is_some_and(|m| !m.is_dir()) { remove_path(&to)?; }
+      std::fs::create_dir_all(&to)?;
+      sync_dir(src, dst, &rel, ignore, synced)?;
+    } else if name == PROJECT_CONF || ty.is_symlink() {
+      let target = std::fs::canonicalize(&from)?;
+      if std::fs::read_link(&to).ok().as_ref() != Some(&target) {
+        if existing.is_some() { remove_path(&to)?; }
+        log(format!("-> {}", rel.display()));
+        symlink(target, &to);
+      }
+    } else if ty.is_file() {
+      let metadata = entry.metadata()?;
+      let unchanged = existing.as_ref().is_some_and(|m| {
+        m.is_file() && m.len() == metadata.len() && m.modified().ok() == metadata.modified().ok()
+      });
+      if !unchanged {
+        // Файл удаляется перед копированием: он может быть доступен только для чтения
+        if existing.is_some() { remove_path(&to)?; }
+        log(format!("-> {}", rel.display()));
+        std::fs::copy(&from, &to)?;
+        File::open(&to)?.set_modified(metadata.modified()?)?;
+      }
+    }
+    
+    synced.insert(rel);
+  }
+  
+  ignore.leave();
   Ok(())
 }
 
+/// Удаляет файл, символическую ссылку или папку, не переходя по символическим ссылкам.
+pub(crate) fn remove_path(path: &Path) -> std::io::Result<()> {
+  match std::fs::symlink_metadata(path) {
+    Ok(metadata) if metadata.is_dir() => std::fs::remove_dir_all(path),
+    Ok(_) => std::fs::remove_file(path),
+    Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+    Err(e) => Err(e),
+  }
+}
+
 pub(crate) fn remove_all(path: impl AsRef<Path>) -> anyhow::Result<()> {
   if path.as_ref().is_file() {
     std::fs::remove_file(path)?;
@@ -120,6 +303,8 @@ pub(crate) fn log(s: impl AsRef<str>) {
 pub(crate) fn generate_build_log_filepath(
   project_name: &str,
   pipeline_short_name: &str,
+  run_id: &str,
+  format: LogFormat,
   cache_dir: &Path,
 ) -> PathBuf {
   use chrono::Local;
@@ -132,18 +317,134 @@ pub(crate) fn generate_build_log_filepath(
   
   let curr_dt = Local::now();
   
-  let log_path = logs_path.join(format!("{}-{}-{}.txt", project_name.replace('/', "-"), pipeline_short_name, curr_dt.format("%Y-%m-%d-%H:%M")));
-  if log_path.exists() { build_log(&log_path, &[LOG_FILE_DELIMETER.to_string()]).expect("Current log file is unwriteable!"); }
+  logs_path.join(format!(
+    "{}-{}-{}-{}.{}",
+    project_name.replace('/', "-"),
+    pipeline_short_name,
+    curr_dt.format("%Y-%m-%d-%H:%M:%S"),
+    run_id,
+    match format {
+      LogFormat::Text => "txt",
+      LogFormat::Json => "jsonl",
+    },
+  ))
+}
+
+/// Формат лога сборки.
+#[derive(Deserialize, Serialize, Clone, Copy, Default, Debug, PartialEq, ValueEnum)]
+pub(crate) enum LogFormat {
+  /// Текст, повторяющий вывод в терминале
+  #[default]
+  Text,
+  /// JSON Lines: по одному объекту на каждое событие сборки
+  Json,
+}
+
+/// Событие лога сборки.
+#[derive(Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub(crate) enum LogEvent<'a> {
+  PipelineStart {
+    project: &'a str,
+    pipeline: &'a str,
+    /// Коммит, из которого подготовлена папка сборки.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    commit: Option<&'a str>,
+  },
+  PipelineEnd {
+    pipeline: &'a str,
+    status: ActionStatus,
+    /// Длительность выполнения в секундах.
+    duration: f64,
+  },
+  ActionStart {
+    action: &'a str,
+    number: usize,
+    total: usize,
+  },
+  ActionEnd {
+    action: &'a str,
+    status: ActionStatus,
+    /// Длительность выполнения в секундах.
+    duration: f64,
+  },
+  /// Запуск команды; сама команда (со скрытыми секретами) не указывается, если её вывод отключён (`show_bash_c`).
+  CommandStart {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    command: Option<&'a str>,
+  },
+  Output {
+    stream: &'static str,
+    #[serde(serialize_with = "plain")]
+    line: &'a str,
+  },
+  Message {
+    #[serde(serialize_with = "plain")]
+    text: &'a str,
+  },
+}
+
+/// Запись лога в формате JSON Lines.
+#[derive(Serialize)]
+struct LogRecord<'a> {
+  ts: String,
+  run_id: &'a str,
+  #[serde(flatten)]
+  event: &'a LogEvent<'a>,
+}
+
+fn plain<S: serde::Serializer>(s: &&str, serializer: S) -> Result<S::Ok, S::Error> {
+  serializer.serialize_str(&strip_ansi_escapes::strip_str(s))
+}
+
+/// Лог сборки текущего запуска Пайплайна.
+#[derive(Clone, Copy)]
+pub(crate) struct BuildLog<'a> {
+  pub(crate) path: &'a Path,
+  pub(crate) format: LogFormat,
+  pub(crate) run_id: &'a str,
+}
+
+impl BuildLog<'_> {
+  /// Записывает события: в текстовый лог - переданные строки, в лог JSON Lines - объекты событий.
+  pub(crate) fn events(&self, events: &[LogEvent], text: &[String]) -> anyhow::Result<()> {
+    match self.format {
+      LogFormat::Text => build_log(self.path, text),
+      LogFormat::Json => {
+        let ts = chrono::Local::now().to_rfc3339();
+        let lines = events
+          .iter()
+          .map(|event| serde_json::to_string(&LogRecord { ts: ts.to_owned(), run_id: self.run_id, event }))
+          .collect::<Result<Vec<_>, _>>()?;
+        build_log(self.path, &lines)
+      },
+    }
+  }
+  
+  pub(crate) fn event(&self, event: LogEvent, text: &[String]) -> anyhow::Result<()> {
+    self.events(std::slice::from_ref(&event), text)
+  }
   
-  log_path
+  /// Записывает сообщения (в логе JSON Lines - как события `message`).
+  pub(crate) fn message(&self, lines: &[String]) -> anyhow::Result<()> {
+    let events = lines.iter().map(|text| LogEvent::Message { text }).collect::<Vec<_>>();
+    self.events(&events, lines)
+  }
+  
+  /// Записывает строки только в текстовый лог.
+  pub(crate) fn text(&self, lines: &[String]) -> anyhow::Result<()> {
+    self.events(&[], lines)
+  }
 }
 
-pub(crate) fn build_log(
+fn build_log(
   path: &Path,
   output: &[String],
 ) -> anyhow::Result<()> {
   use std::io::Write;
   
+  if output.is_empty() { return Ok(()) }
+  
   let file = File::options().create(true).append(true).open(path)?;
   let mut writer = BufWriter::new(file);
   for line in output {
@@ -154,3 +455,165 @@ pub(crate) fn build_log(
   
   Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::tests::TempDir;
+  
+  /// Папки проекта и сборки; функции копирования пишут в лог, который ожидает установки флага подробного вывода.
+  fn dirs() -> (TempDir, TempDir) {
+    let _ = VERBOSE.set(false);
+    (TempDir::new(), TempDir::new())
+  }
+  
+  #[test]
+  fn sync_copies_changes_only() {
+    let (src, dst) = dirs();
+    src.write("src/main.rs", "fn main() {}");
+    src.write("Cargo.toml", "[package]");
+    src.write("artifacts/app", "binary");
+    src.write(PROJECT_CONF, "{}");
+    
+    sync_all(src.path(), dst.path(), &mut IgnoreRules::new(&["artifacts"], &[])).unwrap();
+    assert_eq!(dst.read("src/main.rs").as_deref(), Some("fn main() {}"));
+    assert_eq!(dst.read("Cargo.toml").as_deref(), Some("[package]"));
+    assert!(!dst.path().join("artifacts").exists());
+    assert!(dst.path().join(PROJECT_CONF).is_symlink());
+    
+    let manifest = read_checked::<BTreeSet<PathBuf>>(dst.path().join(SYNC_MANIFEST)).unwrap();
+    assert!(manifest.contains(Path::new("src/main.rs")) && manifest.contains(Path::new("src")));
+    assert!(!manifest.iter().any(|p| p.starts_with("artifacts")));
+    
+    // Файл с тем же размером и временем изменения считается неизменённым и не копируется заново
+    let modified = std::fs::metadata(src.path().join("Cargo.toml")).unwrap().modified().unwrap();
+    dst.write("Cargo.toml", "[PACKAGE]");
+    File::options().write(true).open(dst.path().join("Cargo.toml")).unwrap().set_modified(modified).unwrap();
+    src.write("src/main.rs", "fn main() { println!(); }");
+    
+    sync_all(src.path(), dst.path(), &mut IgnoreRules::new(&["artifacts"], &[])).unwrap();
+    assert_eq!(dst.read("Cargo.toml").as_deref(), Some("[PACKAGE]"));
+    assert_eq!(dst.read("src/main.rs").as_deref(), Some("fn main() { println!(); }"));
+  }
+  
+  #[test]
+  fn sync_removes_deleted_files_only() {
+    let (src, dst) = dirs();
+    src.write("a.txt", "a");
+    src.write("dir/b.txt", "b");
+    src.write("dir/c.txt", "c");
+    
+    sync_all(src.path(), dst.path(), &mut IgnoreRules::new(&[], &[])).unwrap();
+    dst.write("target/out", "built");
+    dst.write("dir/generated.txt", "generated");
+    std::fs::remove_file(src.path().join("a.txt")).unwrap();
+    std::fs::remove_file(src.path().join("dir/b.txt")).unwrap();
+    
+    sync_all(src.path(), dst.path(), &mut IgnoreRules::new(&[], &[])).unwrap();
+    assert_eq!(dst.read("a.txt"), None);
+    assert_eq!(dst.read("dir/b.txt"), None);
+    assert_eq!(dst.read("dir/c.txt").as_deref(), Some("c"));
+    // Файлы, появившиеся в папке сборки во время сборки, сохраняются
+    assert_eq!(dst.read("target/out").as_deref(), Some("built"));
+    assert_eq!(dst.read("dir/generated.txt").as_deref(), Some("generated"));
+    
+    // Удалённая из проекта папка удаляется целиком, вместе с содержимым
+    std::fs::remove_dir_all(src.path().join("dir")).unwrap();
+    sync_all(src.path(), dst.path(), &mut IgnoreRules::new(&[], &[])).unwrap();
+    assert!(!dst.path().join("dir").exists());
+  }
+  
+  #[test]
+  fn sync_replaces_file_with_dir() {
+    let (src, dst) = dirs();
+    src.write("path", "file");
+    sync_all(src.path(), dst.path(), &mut IgnoreRules::new(&[], &[])).unwrap();
+    
+    std::fs::remove_file(src.path().join("path")).unwrap();
+    src.write("path/file", "nested");
+    sync_all(src.path(), dst.path(), &mut IgnoreRules::new(&[], &[])).unwrap();
+    assert_eq!(dst.read("path/file").as_deref(), Some("nested"));
+  }
+  
+  #[test]
+  fn gitignore_rules() {
+    let (src, dst) = dirs();
+    src.write(".gitignore", "*.log\n!keep.log\ntarget/\n");
+    src.write("debug.log", "");
+    src.write("keep.log", "");
+    src.write("target/app", "");
+    src.write("src/target", "");
+    src.write("src/main.rs", "");
+    
+    copy_all(src.path(), dst.path(), &mut IgnoreRules::new(&[], PROJECT_IGNORE_FILES)).unwrap();
+    assert!(!dst.path().join("debug.log").exists());
+    assert!(dst.path().join("keep.log").exists());
+    assert!(!dst.path().join("target").exists());
+    // Шаблон `target/` относится только к папкам
+    assert!(dst.path().join("src/target").exists());
+    assert!(dst.path().join("src/main.rs").exists());
+  }
+  
+  #[test]
+  fn nested_rules_have_priority() {
+    let (src, dst) = dirs();
+    src.write(".gitignore", "*.tmp\n");
+    src.write("a.tmp", "");
+    src.write("sub/.gitignore", "!*.tmp\n");
+    src.write("sub/b.tmp", "");
+    src.write("sub/inner/c.tmp", "");
+    src.write("other/d.tmp", "");
+    
+    copy_all(src.path(), dst.path(), &mut IgnoreRules::new(&[], PROJECT_IGNORE_FILES)).unwrap();
+    assert!(!dst.path().join("a.tmp").exists());
+    assert!(dst.path().join("sub/b.tmp").exists());
+    assert!(dst.path().join("sub/inner/c.tmp").exists());
+    // Правила папки не распространяются на соседние папки
+    assert!(!dst.path().join("other/d.tmp").exists());
+  }
+  
+  #[test]
+  fn deployerignore_overrides_gitignore() {
+    let (src, dst) = dirs();
+    src.write(".gitignore", "*.env\n");
+    src.write(".deployerignore", "!prod.env\ndocs/\n");
+    src.write("dev.env", "");
+    src.write("prod.env", "");
+    src.write("docs/index.md", "");
+    
+    copy_all(src.path(), dst.path(), &mut IgnoreRules::new(&[], PROJECT_IGNORE_FILES)).unwrap();
+    assert!(!dst.path().join("dev.env").exists());
+    assert!(dst.path().join("prod.env").exists());
+    assert!(!dst.path().join("docs").exists());
+  }
+  
+  #[test]
+  fn names_and_project_conf() {
+    let (src, dst) = dirs();
+    src.write(".gitignore", "*.json\n");
+    src.write(PROJECT_CONF, "{}");
+    src.write("data.json", "{}");
+    src.write("artifacts/app", "");
+    src.write("sub/artifacts/app", "");
+    
+    copy_all(src.path(), dst.path(), &mut IgnoreRules::new(&["artifacts"], PROJECT_IGNORE_FILES)).unwrap();
+    // Конфигурация проекта не исключается никакими правилами
+    assert!(dst.path().join(PROJECT_CONF).is_symlink());
+    assert!(!dst.path().join("data.json").exists());
+    assert!(!dst.path().join("artifacts").exists());
+    assert!(!dst.path().join("sub/artifacts").exists());
+  }
+  
+  #[test]
+  fn artifacts_ignore_gitignore() {
+    let (src, dst) = dirs();
+    src.write(".gitignore", "*.bin\n");
+    src.write(".deployerignore", "*.d\n");
+    src.write("app.bin", "");
+    src.write("app.d", "");
+    
+    copy_all(src.path(), dst.path(), &mut IgnoreRules::new(&[], ARTIFACTS_IGNORE_FILES)).unwrap();
+    assert!(dst.path().join("app.bin").exists());
+    assert!(!dst.path().join("app.d").exists());
+  }
+}
@@ -0,0 +1,317 @@
+use base64::{Engine, engine::general_purpose::STANDARD as BASE64};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng, rand_core::RngCore};
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::SECRETS_VAULT;
+use crate::cmd::SecretsType;
+use crate::i18n;
+use crate::rw::{read_checked, remove_path, FileLock};
+
+/// Строка, по расшифровке которой проверяется правильность пароля или ключевого файла.
+const VAULT_CHECK: &str = "deployer";
+/// Файл блокировки хранилища на время его изменения.
+const SECRETS_LOCK: &str = "deploy-secrets.lock";
+
+/// Зашифрованное значение.
+#[derive(Deserialize, Serialize, Clone)]
+struct Encrypted {
+  nonce: String,
+  data: String,
+}
+
+/// Хранилище секретов Деплойера.
+/// 
+/// Хранится в папке конфигурации; названия секретов не шифруются, значения - шифруются
+/// ключом, полученным из пароля или ключевого файла.
+#[derive(Deserialize, Serialize, Default)]
+struct SecretsVault {
+  /// Соль для получения ключа.
+  salt: String,
+  /// Зашифрованная контрольная строка.
+  check: Option<Encrypted>,
+  /// Секреты по названиям проектов.
+  projects: BTreeMap<String, BTreeMap<String, Encrypted>>,
+}
+
+/// Открытое хранилище секретов.
+struct UnlockedVault {
+  vault: SecretsVault,
+  cipher: ChaCha20Poly1305,
+}
+
+impl SecretsVault {
+  /// Открывает хранилище паролем или ключевым файлом.
+  /// 
+  /// Пароль берётся из переменной окружения `DEPLOYER_SECRETS_PASSPHRASE` или запрашивается у пользователя.
+  fn unlock(mut self, key_file: Option<&Path>) -> anyhow::Result<UnlockedVault> {
+    let is_new = self.check.is_none();
+    if is_new {
+      let mut salt = [0u8; 16];
+      OsRng.fill_bytes(&mut salt);
+      self.salt = BASE64.encode(salt);
+    }
+    
+    let secret = match key_file {
+      Some(path) => std::fs::read(path)?,
+      None => match std::env::var("DEPLOYER_SECRETS_PASSPHRASE") {
+        Ok(passphrase) => passphrase.into_bytes(),
+        Err(_) => {
+          let prompt = inquire::Password::new(i18n::SECRETS_PASSPHRASE).with_display_mode(inquire::PasswordDisplayMode::Masked);
+          let prompt = if is_new { prompt.with_custom_confirmation_message(i18n::SECRETS_PASSPHRASE_CONFIRM) } else { prompt.without_confirmation() };
+          prompt.prompt()?.into_bytes()
+        },
+      },
+    };
+    
+    let mut key = [0u8; 32];
+    argon2::Argon2::default()
+      .hash_password_into(&secret, &BASE64.decode(&self.salt)?, &mut key)
+      .map_err(|e| anyhow::anyhow!("Can't derive the key due to: {}", e))?;
+    
+    let mut unlocked = UnlockedVault { vault: self, cipher: ChaCha20Poly1305::new(Key::from_slice(&key)) };
+    match &unlocked.vault.check {
+      Some(check) => if unlocked.decrypt(check).is_err() { return Err(anyhow::anyhow!(i18n::SECRETS_WRONG_PASSPHRASE)) },
+      None => unlocked.vault.check = Some(unlocked.encrypt(VAULT_CHECK)?),
+    }
+    
+    Ok(unlocked)
+  }
+  
+  fn names(&self, project_name: &str) -> Vec<String> {
+    self.projects.get(project_name).map(|secrets| secrets.keys().cloned().collect()).unwrap_or_default()
+  }
+}
+
+impl UnlockedVault {
+  fn encrypt(&self, value: &str) -> anyhow::Result<Encrypted> {
+    let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let data = self.cipher.encrypt(&nonce, value.as_bytes()).map_err(|_| anyhow::anyhow!("Can't encrypt the secret!"))?;
+    Ok(Encrypted { nonce: BASE64.encode(nonce), data: BASE64.encode(data) })
+  }
+  
+  fn decrypt(&self, encrypted: &Encrypted) -> anyhow::Result<String> {
+    let nonce = BASE64.decode(&encrypted.nonce)?;
+    if nonce.len() != 12 { return Err(anyhow::anyhow!("Secret's nonce is corrupted!")) }
+    let data = self.cipher
+      .decrypt(Nonce::from_slice(&nonce), BASE64.decode(&encrypted.data)?.as_slice())
+      .map_err(|_| anyhow::anyhow!(i18n::SECRETS_WRONG_PASSPHRASE))?;
+    Ok(String::from_utf8(data)?)
+  }
+  
+  fn get(&self, project_name: &str, name: &str) -> anyhow::Result<String> {
+    match self.vault.projects.get(project_name).and_then(|secrets| secrets.get(name)) {
+      Some(encrypted) => self.decrypt(encrypted),
+      None => Err(anyhow::anyhow!("{}", i18n::SECRET_NOT_FOUND.replace("{1}", name).replace("{2}", project_name))),
+    }
+  }
+  
+  fn set(&mut self, project_name: &str, name: &str, value: &str) -> anyhow::Result<()> {
+    let encrypted = self.encrypt(value)?;
+    self.vault.projects.entry(project_name.to_owned()).or_default().insert(name.to_owned(), encrypted);
+    Ok(())
+  }
+}
+
+/// Читает хранилище; повреждённый файл не подменяется пустым хранилищем.
+fn load_vault(config_dir: &Path) -> anyhow::Result<SecretsVault> {
+  let path = config_dir.join(SECRETS_VAULT);
+  if !path.exists() { return Ok(SecretsVault::default()) }
+  read_checked(path)
+}
+
+/// Сохраняет хранилище, оставляя доступ к файлу только владельцу.
+/// 
+/// Хранилище записывается во временный файл, который сразу создаётся с доступом только для владельца, и затем
+/// подменяет прежний файл - так файл хранилища ни в какой момент не доступен другим пользователям.
+fn save_vault(config_dir: &Path, vault: &SecretsVault) -> anyhow::Result<()> {
+  use std::io::Write;
+  use std::os::unix::fs::OpenOptionsExt;
+  
+  let path = config_dir.join(SECRETS_VAULT);
+  let tmp_path = config_dir.join(format!("{}.tmp", SECRETS_VAULT));
+  // Оставшийся от прерванной записи временный файл мог быть создан с другими правами
+  remove_path(&tmp_path)?;
+  
+  let mut file = std::fs::OpenOptions::new().write(true).create_new(true).mode(0o600).open(&tmp_path)?;
+  file.write_all(serde_json::to_string_pretty(vault)?.as_bytes())?;
+  file.sync_all()?;
+  std::fs::rename(&tmp_path, &path).map_err(|e| anyhow::anyhow!("Can't save the secrets' vault due to: {}", e))?;
+  
+  Ok(())
+}
+
+/// Изменяет хранилище под межпроцессной блокировкой: иначе секреты, параллельно сохранённые другим Деплойером
+/// между чтением и записью хранилища, будут потеряны.
+fn update_vault(config_dir: &Path, f: impl FnOnce(SecretsVault) -> anyhow::Result<SecretsVault>) -> anyhow::Result<()> {
+  let _lock = FileLock::exclusive(config_dir.join(SECRETS_LOCK))?;
+  save_vault(config_dir, &f(load_vault(config_dir)?)?)
+}
+
+/// Ключевой файл из аргументов или из переменной окружения `DEPLOYER_SECRETS_KEY_FILE`.
+fn key_file(key_file: Option<&Path>) -> Option<PathBuf> {
+  key_file.map(|p| p.to_path_buf()).or_else(|| std::env::var_os("DEPLOYER_SECRETS_KEY_FILE").map(PathBuf::from))
+}
+
+/// Секреты проекта, доступные при сборке.
+/// 
+/// Хранилище открывается только при первом обращении к секрету.
+pub(crate) struct ProjectSecrets {
+  config_dir: PathBuf,
+  project_name: String,
+  key_file: Option<PathBuf>,
+  unlocked: Mutex<Option<UnlockedVault>>,
+  /// Удерживается на время открытия хранилища, чтобы пароль запрашивался только один раз.
+  unlocking: Mutex<()>,
+}
+
+impl ProjectSecrets {
+  pub(crate) fn new(config_dir: &Path, project_name: &str, key_file_path: Option<&Path>) -> Self {
+    Self {
+      config_dir: config_dir.to_path_buf(),
+      project_name: project_name.to_owned(),
+      key_file: key_file(key_file_path),
+      unlocked: Mutex::new(None),
+      unlocking: Mutex::new(()),
+    }
+  }
+  
+  pub(crate) fn get(&self, name: &str) -> anyhow::Result<String> {
+    if let Some(value) = self.get_unlocked(name)? { return Ok(value) }
+    
+    // Пароль запрашивается без блокировки открытого хранилища; другие Пайплайны, которым нужны секреты,
+    // дожидаются открытия хранилища и повторно не спрашивают пароль
+    let _unlocking = self.unlocking.lock().map_err(|_| anyhow::anyhow!("Secrets' vault is poisoned!"))?;
+    if let Some(value) = self.get_unlocked(name)? { return Ok(value) }
+    
+    let vault = load_vault(&self.config_dir)?;
+    if !vault.projects.contains_key(&self.project_name) {
+      return Err(anyhow::anyhow!("{}", i18n::SECRET_NOT_FOUND.replace("{1}", name).replace("{2}", &self.project_name)))
+    }
+    let vault = vault.unlock(self.key_file.as_deref())?;
+    let value = vault.get(&self.project_name, name);
+    *self.unlocked.lock().map_err(|_| anyhow::anyhow!("Secrets' vault is poisoned!"))? = Some(vault);
+    value
+  }
+  
+  /// Возвращает значение секрета, если хранилище уже открыто.
+  fn get_unlocked(&self, name: &str) -> anyhow::Result<Option<String>> {
+    let unlocked = self.unlocked.lock().map_err(|_| anyhow::anyhow!("Secrets' vault is poisoned!"))?;
+    unlocked.as_ref().map(|vault| vault.get(&self.project_name, name)).transpose()
+  }
+}
+
+/// Управляет секретами проекта.
+pub(crate) fn secrets(
+  config_dir: &Path,
+  project_name: &str,
+  key_file_path: Option<&Path>,
+  args: &SecretsType,
+) -> anyhow::Result<()> {
+  let key_file = key_file(key_file_path);
+  
+  match args {
+    SecretsType::Ls => {
+      let names = load_vault(config_dir)?.names(project_name);
+      if names.is_empty() { println!("{}", i18n::SECRETS_EMPTY); }
+      names.iter().for_each(|name| println!("• {}", name.blue().bold()));
+    },
+    SecretsType::Get(args) => {
+      let vault = load_vault(config_dir)?;
+      if !vault.names(project_name).contains(&args.name) {
+        return Err(anyhow::anyhow!("{}", i18n::SECRET_NOT_FOUND.replace("{1}", &args.name).replace("{2}", project_name)))
+      }
+      println!("{}", vault.unlock(key_file.as_deref())?.get(project_name, &args.name)?);
+    },
+    SecretsType::Set(args) => {
+      update_vault(config_dir, |vault| {
+        let mut vault = vault.unlock(key_file.as_deref())?;
+        // Значение не принимается аргументом, чтобы оно не попало в список процессов и историю командной оболочки
+        let value = if args.stdin {
+          let mut value = String::new();
+          std::io::Read::read_to_string(&mut std::io::stdin(), &mut value)?;
+          let value = value.strip_suffix('\n').map(|v| v.strip_suffix('\r').unwrap_or(v)).unwrap_or(&value);
+          value.to_owned()
+        } else {
+          inquire::Password::new(i18n::SECRET_VALUE)
+            .with_display_mode(inquire::PasswordDisplayMode::Masked)
+            .without_confirmation()
+            .prompt()?
+        };
+        vault.set(project_name, &args.name, &value)?;
+        Ok(vault.vault)
+      })?;
+      println!("{}", i18n::SECRET_SAVED.replace("{}", &args.name));
+    },
+    SecretsType::Rm(args) => {
+      update_vault(config_dir, |mut vault| {
+        let secrets = vault.projects.get_mut(project_name);
+        if secrets.and_then(|secrets| secrets.remove(&args.name)).is_none() {
+          return Err(anyhow::anyhow!("{}", i18n::SECRET_NOT_FOUND.replace("{1}", &args.name).replace("{2}", project_name)))
+        }
+        vault.projects.retain(|_, secrets| !secrets.is_empty());
+        Ok(vault)
+      })?;
+      println!("{}", i18n::SECRET_REMOVED.replace("{}", &args.name));
+    },
+  }
+  
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::tests::TempDir;
+  
+  #[test]
+  fn vault_roundtrip() {
+    use std::os::unix::fs::PermissionsExt;
+    
+    let tmp = TempDir::new();
+    let dir = tmp.path();
+    let (key, wrong_key) = (dir.join("key"), dir.join("wrong-key"));
+    std::fs::write(&key, "key").unwrap();
+    std::fs::write(&wrong_key, "wrong key").unwrap();
+    
+    let mut vault = load_vault(dir).unwrap().unlock(Some(&key)).unwrap();
+    vault.set("project", "TOKEN", "secret value").unwrap();
+    save_vault(dir, &vault.vault).unwrap();
+    let mode = std::fs::metadata(dir.join(SECRETS_VAULT)).unwrap().permissions().mode();
+    
+    let secrets = ProjectSecrets::new(dir, "project", Some(&key));
+    let value = secrets.get("TOKEN");
+    let missing = secrets.get("MISSING");
+    let other_project = ProjectSecrets::new(dir, "other", Some(&key)).get("TOKEN");
+    let wrong = load_vault(dir).unwrap().unlock(Some(&wrong_key)).map(|_| ());
+    
+    assert_eq!(mode & 0o777, 0o600);
+    assert_eq!(value.unwrap(), "secret value");
+    assert!(missing.is_err());
+    assert!(other_project.is_err());
+    assert!(wrong.is_err());
+  }
+  
+  #[test]
+  fn concurrent_updates() {
+    let tmp = TempDir::new();
+    let dir = tmp.path();
+    
+    // Без блокировки часть секретов теряется: каждый поток сохраняет хранилище, прочитанное до записей других потоков
+    std::thread::scope(|s| for thread in 0..8 {
+      s.spawn(move || for i in 0..10 {
+        update_vault(dir, |mut vault| {
+          let encrypted = Encrypted { nonce: String::new(), data: String::new() };
+          vault.projects.entry("project".to_owned()).or_default().insert(format!("S{}-{}", thread, i), encrypted);
+          Ok(vault)
+        }).unwrap();
+      });
+    });
+    
+    assert_eq!(load_vault(dir).unwrap().names("project").len(), 80);
+  }
+}
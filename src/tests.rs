@@ -1,3 +1,96 @@
+#[cfg(test)]
+use std::path::{Path, PathBuf};
+
+#[cfg(test)]
+use crate::entities::custom_command::ExitCodes;
+#[cfg(test)]
+use crate::entities::environment::BuildEnvironment;
+#[cfg(test)]
+use crate::entities::variables::{SecretsMask, VarsCache};
+#[cfg(test)]
+use crate::secrets::ProjectSecrets;
+
+#[cfg(feature = "tests")]
 pub(crate) fn tests() -> anyhow::Result<()> {
   Ok(())
 }
+
+/// Временная папка для тестов; удаляется вместе с содержимым, в том числе при провале теста.
+#[cfg(test)]
+pub(crate) struct TempDir(PathBuf);
+
+#[cfg(test)]
+impl TempDir {
+  pub(crate) fn new() -> Self {
+    let path = std::env::temp_dir().join(format!("deployer-test-{}", uuid::Uuid::new_v4().simple()));
+    std::fs::create_dir_all(&path).unwrap();
+    Self(path)
+  }
+  
+  pub(crate) fn path(&self) -> &Path {
+    &self.0
+  }
+  
+  /// Записывает файл по пути относительно папки, создавая родительские папки.
+  pub(crate) fn write(&self, path: &str, content: &str) {
+    let path = self.0.join(path);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, content).unwrap();
+  }
+  
+  /// Читает файл по пути относительно папки; `None`, если файла нет.
+  pub(crate) fn read(&self, path: &str) -> Option<String> {
+    std::fs::read_to_string(self.0.join(path)).ok()
+  }
+}
+
+#[cfg(test)]
+impl Drop for TempDir {
+  fn drop(&mut self) {
+    let _ = std::fs::remove_dir_all(&self.0);
+  }
+}
+
+/// Окружение сборки для тестов; папкой сборки служит временная папка.
+#[cfg(test)]
+pub(crate) struct TestEnv {
+  pub(crate) dir: TempDir,
+  vars_cache: VarsCache,
+  secrets_mask: SecretsMask,
+  exit_codes: ExitCodes,
+  secrets: ProjectSecrets,
+}
+
+#[cfg(test)]
+impl TestEnv {
+  pub(crate) fn new() -> Self {
+    // Выполнение команд пишет в лог, который ожидает установки флага подробного вывода
+    let _ = crate::rw::VERBOSE.set(false);
+    let dir = TempDir::new();
+    let secrets = ProjectSecrets::new(&dir.path().join("config"), "test", None);
+    Self { dir, vars_cache: Default::default(), secrets_mask: Default::default(), exit_codes: Default::default(), secrets }
+  }
+  
+  pub(crate) fn env(&self) -> BuildEnvironment<'_> {
+    BuildEnvironment {
+      build_dir: self.dir.path(),
+      project_dir: self.dir.path(),
+      cache_dir: self.dir.path(),
+      artifacts_dir: self.dir.path(),
+      new_build: true,
+      silent_build: true,
+      no_pipe: false,
+      dry_run: false,
+      vars_cache: &self.vars_cache,
+      secrets_mask: &self.secrets_mask,
+      exit_codes: &self.exit_codes,
+      log_format: Default::default(),
+      log: None,
+      commit: None,
+      default_timeout: None,
+      secrets: &self.secrets,
+      output_prefix: None,
+      injected_placeholders: &[],
+    }
+  }
+}
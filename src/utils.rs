@@ -9,6 +9,30 @@ macro_rules! hmap {
   };
 }
 
+/// Ошибка конфигурации проекта или аргументов запуска.
+#[derive(Debug)]
+pub(crate) struct ConfigError(pub(crate) String);
+
+impl std::fmt::Display for ConfigError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.write_str(&self.0)
+  }
+}
+
+impl std::error::Error for ConfigError {}
+
+/// Команда не завершилась за отведённое время (в секундах) и была остановлена.
+#[derive(Debug)]
+pub(crate) struct CommandTimedOut(pub(crate) u64);
+
+impl std::fmt::Display for CommandTimedOut {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.write_str(&crate::i18n::CMD_TIMED_OUT.replace("{}", &self.0.to_string()))
+  }
+}
+
+impl std::error::Error for CommandTimedOut {}
+
 pub(crate) fn get_current_working_dir() -> std::io::Result<std::path::PathBuf> {
   std::env::current_dir()
 }
@@ -88,3 +112,121 @@ where
   let ordered: std::collections::BTreeMap<_, _> = value.iter().collect();
   ordered.serialize(serializer)
 }
+
+/// Выравнивает таблицу по ширине столбцов; возвращает заголовок, разделитель и строки таблицы.
+pub(crate) fn format_table(header: &[&str], rows: &[Vec<String>]) -> (String, String, Vec<String>) {
+  let widths = (0..header.len())
+    .map(|col| {
+      let header_width = header[col].chars().count();
+      rows.iter().map(|row| row[col].chars().count()).max().unwrap_or_default().max(header_width)
+    })
+    .collect::<Vec<_>>();
+  let format_row = |row: &mut dyn Iterator<Item = &str>| {
+    row.zip(widths.iter()).map(|(cell, width)| format!("{:width$}", cell, width = width)).collect::<Vec<_>>().join(" | ").trim_end().to_owned()
+  };
+  
+  (
+    format_row(&mut header.iter().copied()),
+    widths.iter().map(|w| "-".repeat(*w)).collect::<Vec<_>>().join("-+-"),
+    rows.iter().map(|row| format_row(&mut row.iter().map(|c| c.as_str()))).collect(),
+  )
+}
+
+/// Разбирает длительность вида `30m`, `12h`, `7d` или `2w` (без суффикса - в секундах).
+pub(crate) fn parse_age(s: &str) -> Result<std::time::Duration, String> {
+  let s = s.trim();
+  let (num, mult) = match s.char_indices().last() {
+    Some((i, 's')) => (&s[..i], 1),
+    Some((i, 'm')) => (&s[..i], 60),
+    Some((i, 'h')) => (&s[..i], 60 * 60),
+    Some((i, 'd')) => (&s[..i], 24 * 60 * 60),
+    Some((i, 'w')) => (&s[..i], 7 * 24 * 60 * 60),
+    _ => (s, 1),
+  };
+  let invalid = || format!("invalid duration `{}`, expected e.g. `12h` or `7d`", s);
+  let num = num.trim().parse::<u64>().map_err(|_| invalid())?;
+  Ok(std::time::Duration::from_secs(num.checked_mul(mult).ok_or_else(invalid)?))
+}
+
+/// Разбирает размер вида `500M`, `10G` или `1T` (без суффикса - в байтах).
+pub(crate) fn parse_size(s: &str) -> Result<u64, String> {
+  let s = s.trim();
+  let upper = s.to_uppercase();
+  let trimmed = upper.trim_end_matches("IB").trim_end_matches('B');
+  let (num, mult) = match trimmed.char_indices().last() {
+    Some((i, 'K')) => (&trimmed[..i], 1u64 << 10),
+    Some((i, 'M')) => (&trimmed[..i], 1 << 20),
+    Some((i, 'G')) => (&trimmed[..i], 1 << 30),
+    Some((i, 'T')) => (&trimmed[..i], 1 << 40),
+    _ => (trimmed, 1),
+  };
+  let num = num.trim().parse::<f64>().map_err(|_| format!("invalid size `{}`, expected e.g. `500M` or `10G`", s))?;
+  let size = num * mult as f64;
+  // `as` молча насыщает значение, поэтому отрицательные, бесконечные и слишком большие размеры отклоняются явно
+  if !size.is_finite() || size < 0.0 || size >= u64::MAX as f64 { return Err(format!("invalid size `{}`", s)) }
+  Ok(size as u64)
+}
+
+/// Форматирует размер в байтах для вывода.
+pub(crate) fn format_size(bytes: u64) -> String {
+  const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+  let mut size = bytes as f64;
+  let mut unit = 0;
+  while size >= 1024.0 && unit < UNITS.len() - 1 {
+    size /= 1024.0;
+    unit += 1;
+  }
+  if unit == 0 { format!("{} {}", bytes, UNITS[0]) } else { format!("{:.2} {}", size, UNITS[unit]) }
+}
+
+/// Проверяет, что путь относительный и указывает внутрь папки сборки.
+pub(crate) fn build_relative_path(path: &str) -> anyhow::Result<&std::path::Path> {
+  use std::path::{Component, Path};
+  
+  let p = Path::new(path);
+  if path.is_empty() || p.components().any(|c| !matches!(c, Component::Normal(_) | Component::CurDir)) {
+    return Err(anyhow::anyhow!("Invalid path `{}`: paths must be relative and point inside the build folder!", path))
+  }
+  Ok(p)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::time::Duration;
+  
+  #[test]
+  fn ages() {
+    assert_eq!(parse_age("30").unwrap(), Duration::from_secs(30));
+    assert_eq!(parse_age("30s").unwrap(), Duration::from_secs(30));
+    assert_eq!(parse_age("15m").unwrap(), Duration::from_secs(15 * 60));
+    assert_eq!(parse_age(" 12h ").unwrap(), Duration::from_secs(12 * 60 * 60));
+    assert_eq!(parse_age("7d").unwrap(), Duration::from_secs(7 * 24 * 60 * 60));
+    assert_eq!(parse_age("2w").unwrap(), Duration::from_secs(14 * 24 * 60 * 60));
+    
+    for age in ["", "d", "-1d", "1.5h", "7x", "99999999999999999w", "18446744073709551616"] {
+      assert!(parse_age(age).is_err(), "`{}` must be invalid", age);
+    }
+  }
+  
+  #[test]
+  fn sizes() {
+    assert_eq!(parse_size("512").unwrap(), 512);
+    assert_eq!(parse_size("1K").unwrap(), 1024);
+    assert_eq!(parse_size("500M").unwrap(), 500 << 20);
+    assert_eq!(parse_size("10gb").unwrap(), 10 << 30);
+    assert_eq!(parse_size("1.5GiB").unwrap(), 3 << 29);
+    assert_eq!(parse_size(" 1T ").unwrap(), 1 << 40);
+    
+    for size in ["", "G", "-1G", "10X", "inf", "NaN", "1e30G", "16777216T"] {
+      assert!(parse_size(size).is_err(), "`{}` must be invalid", size);
+    }
+  }
+  
+  #[test]
+  fn formatted_sizes() {
+    assert_eq!(format_size(100), "100 B");
+    assert_eq!(format_size(1536), "1.50 KiB");
+    assert_eq!(format_size(10 << 30), "10.00 GiB");
+  }
+}